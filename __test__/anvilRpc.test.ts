@@ -0,0 +1,102 @@
+import { networkInterfaces } from "node:os";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+const IMPERSONATED = "0x1000000000000000000000000000000000000abc";
+const RECIPIENT = "0x1000000000000000000000000000000000000def";
+
+/**
+ * Test the Hardhat and Anvil compatible methods
+ * Shows how existing eSpace tooling can drive a dev node over its eth port
+ */
+describe("Anvil RPC", () => {
+  test("should serve dev methods next to the eSpace API", async () => {
+    const [jsonrpcHttpEthPort, udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcHttpEthPort,
+      publicEvmRpcApis: "evm,anvil",
+    });
+    await node.pauseMining();
+
+    let id = 0;
+    const call = async (method: string, params: unknown[] = []) => {
+      const response = await fetch(`http://127.0.0.1:${jsonrpcHttpEthPort}`, {
+        method: "POST",
+        headers: { "content-type": "application/json" },
+        body: JSON.stringify({ jsonrpc: "2.0", id: ++id, method, params }),
+      });
+      const { result, error } = await response.json();
+      if (error) throw new Error(error.message);
+      return result;
+    };
+
+    await call("anvil_setBalance", [IMPERSONATED, "0x56bc75e2d63100000"]);
+    expect(await call("eth_getBalance", [IMPERSONATED, "latest"])).toBe(
+      "0x56bc75e2d63100000",
+    );
+
+    const snapshotId = await call("evm_snapshot");
+    await call("hardhat_setBalance", [IMPERSONATED, "0x1"]);
+    expect(await call("evm_revert", [snapshotId])).toBe(true);
+    expect(await call("eth_getBalance", [IMPERSONATED, "latest"])).toBe(
+      "0x56bc75e2d63100000",
+    );
+
+    expect(await call("evm_increaseTime", [3600])).toBe(3600);
+
+    await call("anvil_impersonateAccount", [IMPERSONATED]);
+    const txHash = await call("eth_sendTransaction", [
+      { from: IMPERSONATED, to: RECIPIENT, value: "0x3039" },
+    ]);
+    await call("evm_mine");
+
+    const receipt = await call("eth_getTransactionReceipt", [txHash]);
+    expect(receipt.status).toBe("0x1");
+    expect(await call("eth_getBalance", [RECIPIENT, "latest"])).toBe("0x3039");
+
+    await call("anvil_stopImpersonatingAccount", [IMPERSONATED]);
+    await expect(
+      call("eth_sendTransaction", [{ from: IMPERSONATED, to: RECIPIENT }]),
+    ).rejects.toThrow("is not impersonated");
+
+    // three blocks 100 seconds apart
+    await call("anvil_mine", ["0x3", "0x64"]);
+    const best = await node.getBestEpochNumber();
+    const [first, second, third] = await Promise.all(
+      [best - 2n, best - 1n, best].map(
+        async (epoch) => (await node.getBlockByEpoch(epoch))?.timestamp ?? 0n,
+      ),
+    );
+    expect(second - first).toBe(100n);
+    expect(third - second).toBe(100n);
+  });
+
+  test("should only listen on the loopback address", async () => {
+    const [jsonrpcHttpEthPort, udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcHttpEthPort,
+      publicEvmRpcApis: "evm,anvil",
+    });
+
+    const address = Object.values(networkInterfaces())
+      .flat()
+      .find((info) => info?.family === "IPv4" && !info.internal)?.address;
+    if (!address) return;
+    await expect(
+      fetch(`http://${address}:${jsonrpcHttpEthPort}`, {
+        method: "POST",
+        headers: { "content-type": "application/json" },
+        body: JSON.stringify({ jsonrpc: "2.0", id: 1, method: "eth_chainId" }),
+      }),
+    ).rejects.toThrow();
+  });
+});
@@ -0,0 +1,51 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, OutcomeStatus } from "../index";
+import { EVM_DEPLOY_RAW_TX, EVM_DEPLOYER_PK, getFreePorts } from "./help";
+
+/**
+ * Test native block production
+ * Shows how to mine blocks without the test RPC
+ */
+describe("Block Production", () => {
+  test("should mine empty blocks on the pivot chain", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    const before = await node.getBestEpochNumber();
+    const blocks = await node.mineBlocks(3);
+    expect(blocks).toHaveLength(3);
+    expect(blocks.map((b) => b.epochNumber)).toEqual([
+      before + 1n,
+      before + 2n,
+      before + 3n,
+    ]);
+
+    const block = await node.getBlockByHash(blocks[2].hash);
+    expect(block?.transactions).toEqual([]);
+  });
+
+  test("should mine until a transaction has a receipt", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+      devPackTxImmediately: false,
+    });
+
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    const blocks = await node.mineUntilReceipt(txHash);
+    expect(blocks.length).toBeGreaterThan(0);
+
+    const receipt = await node.getReceipt(txHash);
+    expect(receipt?.outcomeStatus).toBe(OutcomeStatus.Success);
+    expect(blocks.map((b) => b.hash)).toContain(receipt?.blockHash);
+  });
+});
@@ -0,0 +1,50 @@
+import { describe, expect, test } from "vitest";
+import { type BlockEvent, ConfluxNode, type EpochEvent } from "../index";
+import { getFreePorts, waitUntil } from "./help";
+
+/**
+ * Test chain progress callbacks
+ * Shows how to react to new blocks and executed epochs without polling RPC
+ */
+describe("Chain events", () => {
+  test("should report mined blocks and executed epochs", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    const blocks: BlockEvent[] = [];
+    const epochs: EpochEvent[] = [];
+    node.onBlock((block) => blocks.push(block));
+    node.onEpoch((epoch) => epochs.push(epoch));
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const mined = await node.mineBlocks(10);
+    const last = mined[mined.length - 1];
+    await waitUntil(() => blocks.some((b) => b.hash === last.hash));
+
+    const reported = blocks.filter((block) =>
+      mined.some((m) => m.hash === block.hash),
+    );
+    expect(reported.map((block) => block.hash)).toEqual(
+      mined.map((block) => block.hash),
+    );
+    for (const [i, block] of reported.entries()) {
+      expect(block.height).toBe(reported[0].height + BigInt(i));
+      expect(block.timestamp).toBeGreaterThan(0n);
+      // reported on insertion, consensus may not have placed them yet
+      if (block.epochNumber !== undefined) {
+        expect(block.pivot).toBe(true);
+        expect(block.epochNumber).toBe(block.height);
+      }
+    }
+
+    // Execution runs a few epochs behind.
+    await waitUntil(() => epochs.length > 0);
+    for (const [i, epoch] of epochs.entries()) {
+      expect(epoch.blockHashes[epoch.blockHashes.length - 1]).toBe(epoch.hash);
+      if (i > 0) {
+        expect(epoch.epochNumber).toBe(epochs[i - 1].epochNumber + 1n);
+      }
+    }
+  });
+});
@@ -0,0 +1,87 @@
+import { privateKeyToAccount } from "cive/accounts";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, OutcomeStatus, Space } from "../index";
+import {
+  EVM_DEPLOY_RAW_TX,
+  EVM_DEPLOYER_ADDRESS,
+  EVM_DEPLOYER_PK,
+  getFreePorts,
+  TEST_NETWORK_ID,
+  TEST_PRIVATE_KEYS,
+  wait,
+} from "./help";
+
+const GENESIS_BALANCE = 10000n * 10n ** 18n;
+
+/**
+ * Test the typed chain query API
+ * Shows how to read blocks, transactions and accounts without JSON-RPC
+ */
+describe("Chain Query", () => {
+  test("should read blocks, transactions and accounts", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const account = privateKeyToAccount(`0x${TEST_PRIVATE_KEYS[0]}`, {
+      networkId: TEST_NETWORK_ID,
+    });
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      chainId: TEST_NETWORK_ID,
+      evmChainId: 2222,
+      genesisSecrets: TEST_PRIVATE_KEYS,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+      devBlockIntervalMs: 100,
+    });
+
+    const [genesisHash] = await node.getPivotChain(0n, 0n);
+    const genesis = await node.getBlockByEpoch(0n);
+    expect(genesis?.hash).toBe(genesisHash);
+    expect(genesis?.epochNumber).toBe(0n);
+    expect((await node.getBlockByHash(genesisHash))?.height).toBe(0n);
+    expect(await node.getBestEpochNumber()).toBeGreaterThanOrEqual(0n);
+
+    const core = await node.getAccount(account.address, Space.Native);
+    expect(core.balance).toBe(GENESIS_BALANCE);
+    expect(core.nonce).toBe(0n);
+    await expect(
+      node.getAccount("not-an-address", Space.Native),
+    ).rejects.toThrow("invalid base32 address");
+    const evm = await node.getAccount(EVM_DEPLOYER_ADDRESS, Space.Evm);
+    expect(evm.balance).toBe(GENESIS_BALANCE);
+
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    const pending = await node.getTransaction(txHash);
+    expect(pending?.space).toBe(Space.Evm);
+    expect(pending?.from).toBe(EVM_DEPLOYER_ADDRESS.toLowerCase());
+    expect(pending?.to).toBeUndefined();
+
+    let receipt = await node.getReceipt(txHash);
+    for (let i = 0; i < 100 && !receipt; i++) {
+      await wait(200);
+      receipt = await node.getReceipt(txHash);
+    }
+    expect(receipt?.outcomeStatus).toBe(OutcomeStatus.Success);
+    expect(receipt?.gasUsed).toBeGreaterThan(0n);
+
+    const packed = await node.getTransaction(txHash);
+    expect(packed?.blockHash).toBe(receipt?.blockHash);
+    const block = await node.getBlockByHash(receipt?.blockHash ?? "");
+    expect(block?.transactions).toContain(txHash);
+  });
+
+  test("should resolve unknown hashes to null", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const unknownHash = `0x${"ab".repeat(32)}`;
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    expect(await node.getBlockByHash(unknownHash)).toBeNull();
+    expect(await node.getTransaction(unknownHash)).toBeNull();
+    expect(await node.getReceipt(unknownHash)).toBeNull();
+  });
+});
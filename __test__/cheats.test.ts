@@ -0,0 +1,130 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, OutcomeStatus, Space } from "../index";
+import { EVM_DEPLOY_RAW_TX, EVM_DEPLOYER_ADDRESS, getFreePorts } from "./help";
+
+const EVM_ACCOUNT = "0x1234567890123456789012345678901234567890";
+const CORE_ACCOUNT = "0x1000000000000000000000000000000000000001";
+// PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+const RETURN_42 = "0x602a60005260206000f3";
+
+/**
+ * Test writing account state directly
+ * Shows how to set up fixtures without funding transactions or deployments
+ */
+describe("Cheats", () => {
+  test("should set balance and nonce in both spaces", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    await node.setBalance(EVM_ACCOUNT, Space.Evm, 10n ** 18n);
+    await node.setNonce(EVM_ACCOUNT, Space.Evm, 7n);
+    expect(await node.getAccount(EVM_ACCOUNT, Space.Evm)).toEqual({
+      balance: 10n ** 18n,
+      nonce: 7n,
+    });
+
+    await node.request("xcfx_setBalance", [CORE_ACCOUNT, "0x64"]);
+    expect((await node.getAccount(CORE_ACCOUNT, Space.Native)).balance).toBe(
+      100n,
+    );
+    // the same address in the other space is a different account
+    expect((await node.getAccount(CORE_ACCOUNT, Space.Evm)).balance).toBe(0n);
+
+    // state written before new blocks carries over to the next epochs
+    await node.mineBlocks(10);
+    expect((await node.getAccount(EVM_ACCOUNT, Space.Evm)).nonce).toBe(7n);
+  });
+
+  test("should keep a balance set on a fresh node", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    await node.setBalance(EVM_ACCOUNT, Space.Evm, 12345n);
+    expect((await node.getAccount(EVM_ACCOUNT, Space.Evm)).balance).toBe(
+      12345n,
+    );
+
+    await node.mineBlocks(1);
+    expect((await node.getAccount(EVM_ACCOUNT, Space.Evm)).balance).toBe(
+      12345n,
+    );
+  });
+
+  test("should set code and storage", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    await node.setCode(EVM_ACCOUNT, Space.Evm, RETURN_42);
+    await node.setStorageAt(EVM_ACCOUNT, Space.Evm, "0x0", "0x2a");
+
+    expect(await node.request("eth_getCode", [EVM_ACCOUNT, "latest"])).toBe(
+      RETURN_42,
+    );
+    expect(
+      BigInt(
+        await node.request("eth_getStorageAt", [EVM_ACCOUNT, "0x0", "latest"]),
+      ),
+    ).toBe(42n);
+    expect(
+      BigInt(
+        await node.request("eth_call", [{ to: EVM_ACCOUNT, data: "0x" }]),
+      ),
+    ).toBe(42n);
+  });
+
+  test("should fund transactions with a cheated balance", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      devPackTxImmediately: false,
+    });
+    const deployer = () => node.getAccount(EVM_DEPLOYER_ADDRESS, Space.Evm);
+    expect((await deployer()).balance).toBe(0n);
+
+    await node.setBalance(EVM_DEPLOYER_ADDRESS, Space.Evm, 10n ** 18n);
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    await node.mineUntilReceipt(txHash);
+
+    expect((await node.getReceipt(txHash))?.outcomeStatus).toBe(
+      OutcomeStatus.Success,
+    );
+    expect((await deployer()).nonce).toBe(1n);
+  });
+
+  test("should serve the cheats on the eSpace port", async () => {
+    const [jsonrpcHttpEthPort, udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcHttpEthPort,
+      publicEvmRpcApis: "evm,anvil",
+    });
+
+    const response = await fetch(`http://127.0.0.1:${jsonrpcHttpEthPort}`, {
+      method: "POST",
+      headers: { "content-type": "application/json" },
+      body: JSON.stringify({
+        jsonrpc: "2.0",
+        id: 1,
+        method: "xcfx_setBalance",
+        params: [EVM_ACCOUNT, "0x64", "evm"],
+      }),
+    });
+    expect((await response.json()).error).toBeUndefined();
+    expect((await node.getAccount(EVM_ACCOUNT, Space.Evm)).balance).toBe(100n);
+  });
+});
@@ -0,0 +1,60 @@
+import { join } from "node:path";
+import { describe, expect, test } from "vitest";
+import { type ConsoleLog, ConfluxNode, Space } from "../index";
+import { getFreePorts, sleep } from "./help";
+
+const SENDER = "0x1000000000000000000000000000000000000abc";
+const CONTRACT = "0x1000000000000000000000000000000000000c0e";
+
+// Runtime code that calls `console.log("value %s", 42)` and stops.
+const CONSOLE_CODE =
+  "0x60846028600039600060006084600073000000000000000000636f6e736f6c652e6c6f675afa5000" +
+  "b60e72cc" +
+  "0000000000000000000000000000000000000000000000000000000000000040" +
+  "000000000000000000000000000000000000000000000000000000000000002a" +
+  "0000000000000000000000000000000000000000000000000000000000000008" +
+  "76616c7565202573000000000000000000000000000000000000000000000000";
+
+/**
+ * Test capturing console.log output
+ * Shows how to receive the console.log lines of eSpace contracts
+ */
+describe("console.log", () => {
+  test("should report console.log calls with the transaction", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    const logs: ConsoleLog[] = [];
+    node.onConsoleLog((log) => logs.push(log));
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    await node.setCode(CONTRACT, Space.Evm, CONSOLE_CODE);
+    await node.setBalance(SENDER, Space.Evm, 10n ** 20n);
+    await node.impersonateAccount(SENDER, Space.Evm);
+    const txHash = await node.sendUnsignedTransaction({
+      from: SENDER,
+      space: Space.Evm,
+      to: CONTRACT,
+    });
+    await node.mineUntilReceipt(txHash);
+
+    for (let i = 0; i < 50 && logs.length === 0; i++) {
+      await sleep(100);
+    }
+    expect(logs).toEqual([
+      { transactionHash: txHash, depth: 1, message: "value 42" },
+    ]);
+  });
+
+  test("should need traces from a config file", async () => {
+    await using node = new ConfluxNode();
+    node.onConsoleLog(() => {});
+
+    await expect(
+      node.startNode({
+        configFile: join(__dirname, "./fixtures/testConfig/config.toml"),
+      }),
+    ).rejects.toThrow(/executive_trace/);
+  });
+});
@@ -0,0 +1,48 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, CrashKind, type CrashReport, NodeState } from "../index";
+import { getFreePorts, sleep } from "./help";
+
+/**
+ * Test crash reporting
+ * Shows how to find out that the node went away without stopNode
+ */
+describe("Crash report", () => {
+  test("should report a node that exited on its own", async () => {
+    const [udpAndTcpPort, jsonrpcLocalHttpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    const reports: CrashReport[] = [];
+    node.onCrash((report) => reports.push(report));
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcLocalHttpPort,
+    });
+
+    // `stop` signals the exit condvar of the client, like a fatal error does
+    await fetch(`http://127.0.0.1:${jsonrpcLocalHttpPort}`, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify({
+        jsonrpc: "2.0",
+        id: 1,
+        method: "stop",
+        params: [],
+      }),
+    }).catch(() => {});
+
+    for (let i = 0; i < 50 && reports.length === 0; i++) {
+      await sleep(100);
+    }
+    expect(reports).toHaveLength(1);
+    expect(reports[0].kind).toBe(CrashKind.Exit);
+    expect(reports[0].message).toBe(
+      "The node exited without stop_node being called",
+    );
+    expect(reports[0].backtrace ?? null).toBeNull();
+
+    const status = node.getStatus();
+    expect(status.state).toBe(NodeState.Crashed);
+    expect(status.lastError).toBe(reports[0].message);
+  });
+});
@@ -0,0 +1,57 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test custom block generation
+ * Shows how to build competing branches and force pivot chain reorgs
+ */
+describe("Custom Blocks", () => {
+  test("should build blocks with custom parents, referees and timestamps", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const [genesisHash] = await node.getPivotChain(0n, 0n);
+    const pivot = await node.mineBlocks(3);
+
+    // a side block on genesis doesn't move the pivot chain
+    const side = await node.mineCustomBlock({ parentHash: genesisHash });
+    expect(await node.getBestEpochNumber()).toBe(pivot[2].epochNumber);
+
+    const timestamp = BigInt(Math.floor(Date.now() / 1000)) + 3600n;
+    const merged = await node.mineCustomBlock({
+      parentHash: pivot[2].hash,
+      refereeHashes: [side.hash],
+      timestamp,
+    });
+    const block = await node.getBlockByHash(merged.hash);
+    expect(block?.parentHash).toBe(pivot[2].hash);
+    expect(block?.refereeHashes).toEqual([side.hash]);
+    expect(block?.timestamp).toBe(timestamp);
+  });
+
+  test("should replace the pivot chain with a heavier branch", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const mined = await node.mineBlocks(5);
+    const { removedPivotHashes, newBlocks } = await node.reorg(2);
+    expect(removedPivotHashes).toEqual(mined.slice(3).map((b) => b.hash));
+    expect(newBlocks).toHaveLength(3);
+
+    const best = await node.getBestEpochNumber();
+    const tail = await node.getPivotChain(best - 2n, best);
+    expect(tail).toEqual(newBlocks.map((b) => b.hash));
+    expect(newBlocks[0].epochNumber).toBe(mined[3].epochNumber);
+
+    // the replaced blocks still exist, they are just off the pivot chain
+    const orphan = await node.getBlockByHash(removedPivotHashes[0]);
+    expect(orphan?.hash).toBe(removedPivotHashes[0]);
+  });
+});
@@ -0,0 +1,32 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, NodeState } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test automatic teardown
+ * Shows how to tie the node lifetime to a scope with `await using`
+ */
+describe("Dispose", () => {
+  test("should stop the node at the end of the scope", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    let node: ConfluxNode;
+    {
+      await using scoped = new ConfluxNode();
+      node = scoped;
+      await scoped.startNode({
+        tcpPort: udpAndTcpPort,
+        udpPort: udpAndTcpPort,
+      });
+      expect(scoped.getStatus().state).toBe(NodeState.Running);
+    }
+
+    expect(node.getStatus().state).toBe(NodeState.Stopped);
+  });
+
+  test("should be a no-op for a node that is not running", async () => {
+    const node = new ConfluxNode();
+    await expect(node.dispose()).resolves.toBeNull();
+    await expect(node[Symbol.asyncDispose]()).resolves.toBeUndefined();
+    expect(node.getStatus().state).toBe(NodeState.Idle);
+  });
+});
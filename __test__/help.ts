@@ -40,6 +40,28 @@ export async function getFreePorts() {
 export const wait = (ms: number) =>
   new Promise((resolve) => setTimeout(resolve, ms));
 
+/**
+ * Poll `check` until it returns a truthy value
+ * @param timeoutMs - How long to poll before failing
+ * @returns The first truthy value
+ */
+export const waitUntil = async <T>(
+  check: () => T | Promise<T>,
+  timeoutMs = 10_000,
+): Promise<T> => {
+  const deadline = Date.now() + timeoutMs;
+  for (;;) {
+    const value = await check();
+    if (value) {
+      return value;
+    }
+    if (Date.now() >= deadline) {
+      throw new Error(`Condition not met within ${timeoutMs}ms`);
+    }
+    await wait(100);
+  }
+};
+
 export const TEST_NETWORK_ID = 1111;
 export const TEST_MINING_ADDRESS = "0x1b13CC31fC4Ceca3b72e3cc6048E7fabaefB3AC3";
 export const TEST_MINING_ADDRESS_PK =
@@ -57,6 +79,16 @@ export const TEST_PRIVATE_KEYS = [
   "e9cfd4d1d29f7a67c970c1d5c145e958061cd54d05a83e29c7e39b7be894c9c6",
 ];
 
+// An eSpace account, funded through `genesisEvmSecrets`.
+export const EVM_DEPLOYER_PK =
+  "5674ac1fad4a1ce43e94917994c8f0c81140c4bbe807dbdc4945e0db5357f933";
+export const EVM_DEPLOYER_ADDRESS =
+  "0x79135a10B85be94eaA9bF23c21F2Eff3b055d498";
+// Deploys an empty contract from `EVM_DEPLOYER_ADDRESS` at nonce 0, signed for
+// evmChainId 2222.
+export const EVM_DEPLOY_RAW_TX =
+  "0xf85780843b9aca00830f424080808560006000f382117fa09cb710949106adf559c101b9e0b654f2209a3979879072a367cd56f5e0e1149da001c063c38b46e1a058678359a650ea3cfb073b7e1da8839a7ae5e835fc8ee632";
+
 export const localChain = defineChain({
   name: "local",
   id: TEST_NETWORK_ID,
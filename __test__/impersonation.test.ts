@@ -0,0 +1,83 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, OutcomeStatus, Space } from "../index";
+import { getFreePorts } from "./help";
+
+const IMPERSONATED = "0x1000000000000000000000000000000000000abc";
+const OTHER_IMPERSONATED = "0x1000000000000000000000000000000000000bcd";
+const RECIPIENT = "0x1000000000000000000000000000000000000def";
+
+/**
+ * Test impersonating senders
+ * Shows how to send transactions as addresses without their private keys
+ */
+describe("Impersonation", () => {
+  test.each([Space.Evm, Space.Native])(
+    "should execute unsigned transactions in the %s space",
+    async (space) => {
+      const [udpAndTcpPort] = await getFreePorts();
+
+      await using node = new ConfluxNode();
+      await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+      await node.pauseMining();
+      await node.setBalance(IMPERSONATED, space, 10n ** 20n);
+
+      const transaction = {
+        from: IMPERSONATED,
+        space,
+        to: RECIPIENT,
+        value: 12345n,
+      };
+      await expect(node.sendUnsignedTransaction(transaction)).rejects.toThrow(
+        "is not impersonated",
+      );
+
+      await node.impersonateAccount(IMPERSONATED, space);
+      const txHash = await node.sendUnsignedTransaction(transaction);
+      await node.mineUntilReceipt(txHash);
+
+      const receipt = await node.getReceipt(txHash);
+      expect(receipt?.outcomeStatus).toBe(OutcomeStatus.Success);
+      expect((await node.getAccount(RECIPIENT, space)).balance).toBe(12345n);
+      expect((await node.getAccount(IMPERSONATED, space)).nonce).toBe(1n);
+
+      expect(await node.stopImpersonatingAccount(IMPERSONATED, space)).toBe(
+        true,
+      );
+      await expect(node.sendUnsignedTransaction(transaction)).rejects.toThrow(
+        "is not impersonated",
+      );
+    },
+  );
+
+  test("should keep the senders of equal transactions apart", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const send = async (from: string) => {
+      await node.setBalance(from, Space.Evm, 10n ** 20n);
+      await node.impersonateAccount(from, Space.Evm);
+      return node.sendUnsignedTransaction({
+        from,
+        space: Space.Evm,
+        to: RECIPIENT,
+        value: 1n,
+        nonce: 0n,
+      });
+    };
+    const first = await send(IMPERSONATED);
+    const second = await send(OTHER_IMPERSONATED);
+    expect(first).not.toBe(second);
+
+    await node.mineUntilReceipt(first);
+    await node.mineUntilReceipt(second);
+    expect((await node.getAccount(RECIPIENT, Space.Evm)).balance).toBe(2n);
+
+    // a restart empties the block cache, the senders are read back from disk
+    await node.restartNode();
+    expect((await node.getTransaction(first))?.from).toBe(IMPERSONATED);
+    expect((await node.getTransaction(second))?.from).toBe(OTHER_IMPERSONATED);
+  });
+});
@@ -0,0 +1,65 @@
+import { privateKeyToAccount } from "cive/accounts";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts, TEST_NETWORK_ID, TEST_PRIVATE_KEYS } from "./help";
+
+/**
+ * Test in-process JSON-RPC
+ * Shows how to talk to a node without opening any JSON-RPC port
+ */
+describe("In-process RPC", () => {
+  test("should serve core space and eSpace requests without ports", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const account = privateKeyToAccount(`0x${TEST_PRIVATE_KEYS[0]}`, {
+      networkId: TEST_NETWORK_ID,
+    });
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      chainId: TEST_NETWORK_ID,
+      evmChainId: 2222,
+      genesisSecrets: TEST_PRIVATE_KEYS,
+    });
+
+    const epochNumber = await node.request("cfx_epochNumber");
+    expect(BigInt(epochNumber)).toBeGreaterThanOrEqual(0n);
+
+    const balance = await node.request("cfx_getBalance", [account.address]);
+    expect(BigInt(balance)).toBe(10000n * 10n ** 18n);
+
+    const status = await node.request("cfx_getStatus");
+    expect(BigInt(status.chainId)).toBe(BigInt(TEST_NETWORK_ID));
+
+    const chainId = await node.request("eth_chainId");
+    expect(BigInt(chainId)).toBe(2222n);
+
+    await expect(
+      node.request("cfx_getBalance", ["not-an-address"]),
+    ).rejects.toThrow(/invalid base32 address/i);
+  });
+
+  test("should answer every call of a batch", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    const [blockNumber, missing] = await node.requestBatch([
+      { method: "eth_blockNumber" },
+      { method: "no_suchMethod", params: [] },
+    ]);
+    expect(blockNumber.error).toBeUndefined();
+    expect(typeof blockNumber.result).toBe("string");
+    expect(missing.result).toBeUndefined();
+    expect(missing.error?.code).toBe(-32601);
+  });
+
+  test("should reject requests while the node is not running", async () => {
+    const node = new ConfluxNode();
+    await expect(node.request("cfx_epochNumber")).rejects.toThrow(
+      "Node is not running",
+    );
+  });
+});
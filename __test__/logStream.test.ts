@@ -0,0 +1,62 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, Space } from "../index";
+import { getFreePorts } from "./help";
+
+const SENDER = "0x1000000000000000000000000000000000000abc";
+const CONTRACT = "0x1000000000000000000000000000000000000106";
+
+// Runtime code that emits a log with topic 0x2a and the word 0xff as data.
+const LOG_CODE = "0x60ff600052602a60206000a100";
+const TOPIC = `0x${"2a".padStart(64, "0")}`;
+
+/**
+ * Test streaming logs
+ * Shows how to follow the logs of a contract with `for await`
+ */
+describe("logs", () => {
+  test("should stream the logs of executed transactions", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const stream = await node.logs({
+      space: Space.Evm,
+      address: [CONTRACT],
+      topics: [[TOPIC]],
+    });
+
+    await node.setCode(CONTRACT, Space.Evm, LOG_CODE);
+    await node.setBalance(SENDER, Space.Evm, 10n ** 20n);
+    await node.impersonateAccount(SENDER, Space.Evm);
+    const txHash = await node.sendUnsignedTransaction({
+      from: SENDER,
+      space: Space.Evm,
+      to: CONTRACT,
+    });
+    await node.mineUntilReceipt(txHash);
+    const receipt = await node.getReceipt(txHash);
+
+    for await (const log of stream) {
+      expect(log).toMatchObject({
+        address: CONTRACT,
+        space: Space.Evm,
+        topics: [TOPIC],
+        data: `0x${"ff".padStart(64, "0")}`,
+        transactionHash: txHash,
+        transactionIndex: 0,
+        logIndex: 0,
+        blockHash: receipt?.blockHash,
+        epochNumber: receipt?.epochNumber,
+        removed: false,
+      });
+      break;
+    }
+  });
+
+  test("should reject logs on a stopped node", async () => {
+    const node = new ConfluxNode();
+    await expect(node.logs()).rejects.toThrow("Node is not running");
+  });
+});
@@ -0,0 +1,123 @@
+import { privateKeyToAccount } from "cive/accounts";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import {
+  EVM_DEPLOY_RAW_TX,
+  EVM_DEPLOYER_PK,
+  getFreePorts,
+  TEST_NETWORK_ID,
+  TEST_PRIVATE_KEYS,
+  wait,
+  waitUntil,
+} from "./help";
+
+/**
+ * Test switching the block production mode at runtime
+ * Shows how to move between automine, interval and manual mining
+ */
+describe("Mining Mode", () => {
+  test("should switch between interval and manual mining", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      devBlockIntervalMs: 100,
+    });
+    expect(await node.getMiningMode()).toMatchObject({
+      automine: false,
+      intervalMs: 100,
+      paused: false,
+    });
+
+    // the mode changes resolve once the block in progress is done, so no
+    // block may show up during the idle windows
+    await node.pauseMining();
+    const paused = await node.getBestEpochNumber();
+    await wait(500);
+    expect(await node.getBestEpochNumber()).toBe(paused);
+
+    await node.resumeMining();
+    await waitUntil(async () => (await node.getBestEpochNumber()) > paused);
+
+    await node.setIntervalMining(0);
+    const manual = await node.getBestEpochNumber();
+    await wait(500);
+    expect(await node.getBestEpochNumber()).toBe(manual);
+  });
+
+  test("should automine new transactions once enabled", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+      devPackTxImmediately: false,
+    });
+    expect((await node.getMiningMode()).automine).toBe(false);
+
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    // nothing mines on this node until automine is on
+    expect(await node.getReceipt(txHash)).toBeNull();
+
+    await node.setAutomine(true);
+    await waitUntil(() => node.getReceipt(txHash));
+  });
+
+  test("should not automine for transactions that can't be packed", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const account = privateKeyToAccount(`0x${TEST_PRIVATE_KEYS[0]}`, {
+      networkId: TEST_NETWORK_ID,
+    });
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      chainId: TEST_NETWORK_ID,
+      genesisSecrets: TEST_PRIVATE_KEYS,
+    });
+    expect((await node.getMiningMode()).automine).toBe(true);
+
+    // Nonce 0 was never sent, so this transaction waits in the pool.
+    const raw = await account.signTransaction({
+      to: account.address,
+      value: 1n,
+      nonce: 1,
+      gas: 21000n,
+      gasPrice: 10n ** 9n,
+      storageLimit: 0n,
+      epochHeight: 0n,
+      chainId: TEST_NETWORK_ID,
+    });
+    await node.request("cfx_sendRawTransaction", [raw]);
+
+    const idle = await node.getBestEpochNumber();
+    await wait(500);
+    expect(await node.getBestEpochNumber()).toBe(idle);
+  });
+
+  test("should pack transactions sent through request", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+    });
+    expect((await node.getMiningMode()).automine).toBe(true);
+
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    expect((await node.getTransaction(txHash))?.blockHash).toBeTruthy();
+  });
+});
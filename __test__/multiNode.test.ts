@@ -0,0 +1,99 @@
+import { mkdtempSync, readFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { createPublicClient, http } from "cive";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test running several nodes in one process
+ * Shows how to:
+ * 1. Start two independent dev chains side by side
+ * 2. Keep the files of each node in its own data directory
+ * 3. Reject a node whose process-wide settings conflict with a running one
+ */
+describe("Multiple nodes in one process", () => {
+  test("should run two independent nodes", async () => {
+    const [httpPortA, p2pPortA, httpPortB] = await getFreePorts();
+    const [p2pPortB] = await getFreePorts();
+
+    const nodeA = new ConfluxNode();
+    const nodeB = new ConfluxNode();
+
+    await nodeA.startNode({
+      chainId: 2001,
+      evmChainId: 2002,
+      tcpPort: p2pPortA,
+      udpPort: p2pPortA,
+      jsonrpcHttpPort: httpPortA,
+    });
+    await nodeB.startNode({
+      chainId: 3001,
+      evmChainId: 3002,
+      tcpPort: p2pPortB,
+      udpPort: p2pPortB,
+      jsonrpcHttpPort: httpPortB,
+    });
+
+    const clientA = createPublicClient({
+      transport: http(`http://127.0.0.1:${httpPortA}`),
+    });
+    const clientB = createPublicClient({
+      transport: http(`http://127.0.0.1:${httpPortB}`),
+    });
+
+    expect((await clientA.getStatus()).chainId).toBe(2001);
+    expect((await clientB.getStatus()).chainId).toBe(3001);
+
+    await nodeA.stopNode();
+    await nodeB.stopNode();
+  });
+
+  test("should resolve PoS paths against each data directory", async () => {
+    const [p2pPortA, p2pPortB] = await getFreePorts();
+    const cwd = process.cwd();
+    const dirA = mkdtempSync(join(tmpdir(), "xcfx-a-"));
+    const dirB = mkdtempSync(join(tmpdir(), "xcfx-b-"));
+
+    await using nodeA = new ConfluxNode();
+    await using nodeB = new ConfluxNode();
+    await nodeA.startNode({
+      confluxDataDir: dirA,
+      tcpPort: p2pPortA,
+      udpPort: p2pPortA,
+    });
+    await nodeB.startNode({
+      confluxDataDir: dirB,
+      tcpPort: p2pPortB,
+      udpPort: p2pPortB,
+    });
+
+    expect(readFileSync(join(dirA, "pos_config.yaml"), "utf8")).toContain(
+      join(dirA, "log/pos.log"),
+    );
+    expect(readFileSync(join(dirB, "pos_config.yaml"), "utf8")).toContain(
+      join(dirB, "log/pos.log"),
+    );
+    expect(process.cwd()).toBe(cwd);
+  });
+
+  test("should reject a conflicting cip112 transition height", async () => {
+    const [p2pPortA, p2pPortB] = await getFreePorts();
+
+    const nodeA = new ConfluxNode();
+    const nodeB = new ConfluxNode();
+
+    await nodeA.startNode({ tcpPort: p2pPortA, udpPort: p2pPortA });
+
+    await expect(
+      nodeB.startNode({
+        tcpPort: p2pPortB,
+        udpPort: p2pPortB,
+        cip112TransitionHeight: 100,
+      }),
+    ).rejects.toThrow(/cip112_transition_height/);
+
+    await nodeA.stopNode();
+  });
+});
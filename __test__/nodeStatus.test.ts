@@ -0,0 +1,100 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, NodeState, type StateTransition } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test the node state machine
+ * Shows how to observe the node lifecycle without polling RPC ports
+ */
+describe("Node Status", () => {
+  test("should report state transitions", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    const transitions: StateTransition[] = [];
+    node.onStateChange((transition) => transitions.push(transition));
+
+    expect(node.getStatus().state).toBe(NodeState.Idle);
+
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    expect(node.getStatus().state).toBe(NodeState.Running);
+
+    const report = await node.stopNode({ timeoutMs: 30000 });
+    expect(report.forced).toBe(false);
+    expect(report.dbFlushed).toBe(true);
+    expect(report.lastExecutedEpoch).toBeGreaterThanOrEqual(0);
+    const status = node.getStatus();
+    expect(status.state).toBe(NodeState.Stopped);
+    expect(status.transitions.map((t) => t.state)).toEqual([
+      NodeState.Idle,
+      NodeState.Starting,
+      NodeState.Running,
+      NodeState.Stopping,
+      NodeState.Stopped,
+    ]);
+
+    // callbacks are delivered asynchronously
+    await new Promise((resolve) => setImmediate(resolve));
+    expect(transitions.map((t) => t.state)).toEqual([
+      NodeState.Starting,
+      NodeState.Running,
+      NodeState.Stopping,
+      NodeState.Stopped,
+    ]);
+  });
+
+  test("should report a node that died during boot", async () => {
+    const node = new ConfluxNode();
+
+    await expect(
+      node.startNode({ configFile: "/path/does/not/exist.toml" }),
+    ).rejects.toThrow();
+
+    const status = node.getStatus();
+    expect(status.state).toBe(NodeState.Crashed);
+    expect(status.lastError).toBeDefined();
+  });
+
+  test("should cancel a pending start", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    const controller = new AbortController();
+
+    const start = node.startNode(
+      { tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort },
+      { signal: controller.signal },
+    );
+    controller.abort();
+
+    await expect(start).rejects.toThrow(/cancelled/);
+    expect(node.getStatus().state).toBe(NodeState.Stopped);
+  });
+
+  test("should cancel a start that did not get to run yet", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+
+    const start = node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+    });
+    expect(node.cancelStart()).toBe(true);
+
+    await expect(start).rejects.toThrow(/cancelled/);
+    expect(node.getStatus().state).toBe(NodeState.Stopped);
+    expect(node.cancelStart()).toBe(false);
+  });
+
+  test("should time out a start that takes too long", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+
+    await expect(
+      node.startNode({
+        tcpPort: udpAndTcpPort,
+        udpPort: udpAndTcpPort,
+        startupTimeoutMs: 1,
+      }),
+    ).rejects.toThrow(/Timeout Error/);
+    expect(node.getStatus().state).toBe(NodeState.Crashed);
+  });
+});
@@ -0,0 +1,35 @@
+import { createTestClient, http, webSocket } from "cive";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test native readiness
+ * startNode only resolves once the node can serve requests, no polling needed
+ */
+describe("Readiness", () => {
+  test("should serve requests as soon as startNode resolves", async () => {
+    const [jsonrpcHttpPort, jsonrpcWsPort, udpAndTcpPort] =
+      await getFreePorts();
+    const node = new ConfluxNode();
+
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcHttpPort,
+      jsonrpcWsPort,
+    });
+
+    const httpClient = createTestClient({
+      transport: http(`http://127.0.0.1:${jsonrpcHttpPort}`),
+    });
+    const wsClient = createTestClient({
+      transport: webSocket(`ws://127.0.0.1:${jsonrpcWsPort}`),
+    });
+
+    expect(await httpClient.getCurrentSyncPhase()).toBe("NormalSyncPhase");
+    expect(await wsClient.getCurrentSyncPhase()).toBe("NormalSyncPhase");
+
+    await node.stopNode();
+  });
+});
@@ -0,0 +1,62 @@
+import { createPublicClient, createTestClient, http } from "cive";
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test the node lifecycle
+ * Shows that the same ConfluxNode object can be started and stopped repeatedly
+ */
+describe("Node Lifecycle", () => {
+  test("should start the same node again after stop", async () => {
+    const [jsonrpcHttpPort, udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    const client = createPublicClient({
+      transport: http(`http://127.0.0.1:${jsonrpcHttpPort}`),
+    });
+
+    for (let i = 0; i < 3; i++) {
+      await node.startNode({
+        tcpPort: udpAndTcpPort,
+        udpPort: udpAndTcpPort,
+        jsonrpcHttpPort,
+      });
+
+      const status = await client.getStatus();
+      expect(status.chainId).toBe(1234);
+
+      await node.stopNode();
+      await expect(async () => client.getStatus()).rejects.toThrow();
+    }
+
+    await expect(node.stopNode()).rejects.toThrow("Node is not running");
+  });
+
+  test("should restart with the same chain data", async () => {
+    const [jsonrpcHttpPort, udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    const transport = http(`http://127.0.0.1:${jsonrpcHttpPort}`);
+    const client = createPublicClient({ transport });
+    const testClient = createTestClient({ transport });
+
+    // no confluxDataDir, the node runs in a temp directory
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      jsonrpcHttpPort,
+    });
+
+    await testClient.mine({ blocks: 10 });
+    const epochNumber = await client.getEpochNumber();
+
+    const { resumedEpoch } = await node.restartNode({
+      devBlockIntervalMs: 100,
+    });
+    expect(resumedEpoch).greaterThanOrEqual(Number(epochNumber));
+
+    const epochNumber2 = await client.getEpochNumber();
+    expect(Number(epochNumber2)).greaterThanOrEqual(Number(epochNumber));
+
+    await node.stopNode();
+  });
+});
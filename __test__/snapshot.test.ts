@@ -0,0 +1,96 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, Space } from "../index";
+import {
+  EVM_DEPLOY_RAW_TX,
+  EVM_DEPLOYER_ADDRESS,
+  EVM_DEPLOYER_PK,
+  getFreePorts,
+} from "./help";
+
+/**
+ * Test snapshots of a running node
+ * Shows how to isolate tests that share one long-lived chain
+ */
+describe("Snapshot", () => {
+  test("should revert state and pool to the snapshot", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+      devPackTxImmediately: false,
+    });
+    await node.mineBlocks(5);
+
+    const deployer = () => node.getAccount(EVM_DEPLOYER_ADDRESS, Space.Evm);
+    const id = await node.snapshot();
+    const before = await deployer();
+
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    await node.mineUntilReceipt(txHash);
+    expect((await deployer()).nonce).toBe(1n);
+
+    expect(await node.revert(id)).toBe(true);
+    expect(await deployer()).toEqual(before);
+    expect(await node.getReceipt(txHash)).toBeNull();
+
+    // a snapshot can only be reverted to once
+    expect(await node.revert(id)).toBe(false);
+  });
+
+  test("should keep reverted transactions out of later blocks", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      evmChainId: 2222,
+      genesisEvmSecrets: [EVM_DEPLOYER_PK],
+      devPackTxImmediately: false,
+    });
+    await node.mineBlocks(5);
+
+    const id = await node.snapshot();
+    const before = await node.getAccount(EVM_DEPLOYER_ADDRESS, Space.Evm);
+    const txHash = await node.request("eth_sendRawTransaction", [
+      EVM_DEPLOY_RAW_TX,
+    ]);
+    await node.mineUntilReceipt(txHash);
+    expect(await node.revert(id)).toBe(true);
+
+    // the abandoned branch must not come back as a referee
+    await node.mineBlocks(10);
+    const after = await node.getAccount(EVM_DEPLOYER_ADDRESS, Space.Evm);
+    expect(after.balance).toBe(before.balance);
+    expect(after.nonce).toBe(before.nonce);
+    expect(await node.getReceipt(txHash)).toBeNull();
+  });
+
+  test("should drop later snapshots on revert", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const epoch = await node.getBestEpochNumber();
+    const [tip] = await node.getPivotChain(epoch, epoch);
+    const first = await node.snapshot();
+    const mined = await node.mineBlocks(3);
+    const second = await node.snapshot();
+
+    expect(await node.revert(first)).toBe(true);
+    expect(await node.revert(second)).toBe(false);
+
+    // the snapshot tip stays, the blocks mined after it left the pivot chain
+    const pivotChain = await node.getPivotChain(epoch);
+    expect(pivotChain[0]).toBe(tip);
+    expect(pivotChain).not.toContain(mined[0].hash);
+  });
+});
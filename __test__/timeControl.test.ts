@@ -0,0 +1,62 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode } from "../index";
+import { getFreePorts } from "./help";
+
+/**
+ * Test controlling block timestamps
+ * Shows how to fast forward time for time locks and vesting schedules
+ */
+describe("Time Control", () => {
+  test("should use the set timestamp and continue from there", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    const timestamp = BigInt(Math.floor(Date.now() / 1000)) + 86400n;
+    await node.setNextBlockTimestamp(timestamp);
+    const [first, second] = await node.mineBlocks(2);
+
+    const firstBlock = await node.getBlockByHash(first.hash);
+    const secondBlock = await node.getBlockByHash(second.hash);
+    expect(firstBlock?.timestamp).toBe(timestamp);
+    expect(secondBlock?.timestamp).toBeGreaterThanOrEqual(timestamp);
+    expect(secondBlock?.timestamp).toBeLessThan(timestamp + 60n);
+
+    // the eSpace block of an epoch has the timestamp of its pivot block
+    const evmBlock = (await node.request("eth_getBlockByNumber", [
+      `0x${first.epochNumber?.toString(16)}`,
+      false,
+    ])) as { timestamp: string };
+    expect(BigInt(evmBlock.timestamp)).toBe(timestamp);
+
+    await expect(node.setNextBlockTimestamp(timestamp - 1n)).rejects.toThrow(
+      "is before",
+    );
+  });
+
+  test("should increase time and advance in fixed steps", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    expect(await node.increaseTime(3600n)).toBe(3600n);
+    const [shifted] = await node.mineBlocks(1);
+    const shiftedBlock = await node.getBlockByHash(shifted.hash);
+    const now = BigInt(Math.floor(Date.now() / 1000));
+    expect(shiftedBlock?.timestamp).toBeGreaterThanOrEqual(now + 3590n);
+
+    await node.setBlockTimestampInterval(15);
+    const stepped = await node.mineBlocks(3);
+    const timestamps = await Promise.all(
+      stepped.map(
+        async (block) => (await node.getBlockByHash(block.hash))?.timestamp,
+      ),
+    );
+    const base = shiftedBlock?.timestamp ?? 0n;
+    expect(timestamps).toEqual([base + 15n, base + 30n, base + 45n]);
+  });
+});
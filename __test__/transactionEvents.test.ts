@@ -0,0 +1,96 @@
+import { describe, expect, test } from "vitest";
+import {
+  ConfluxNode,
+  OutcomeStatus,
+  Space,
+  type TransactionEvent,
+  TransactionStage,
+} from "../index";
+import { EVM_DEPLOY_RAW_TX, getFreePorts, sleep } from "./help";
+
+const SENDER = "0x1000000000000000000000000000000000000abc";
+const RECIPIENT = "0x1000000000000000000000000000000000000def";
+
+/**
+ * Test transaction lifecycle events
+ * Shows how to follow a transaction from the pool to its receipt
+ */
+describe("Transaction events", () => {
+  test("should report every stage of a transaction", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    const events: TransactionEvent[] = [];
+    node.onTransaction((event) => events.push(event), { senders: [SENDER] });
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+
+    await node.setBalance(SENDER, Space.Evm, 10n ** 20n);
+    await node.impersonateAccount(SENDER, Space.Evm);
+
+    await expect(
+      node.sendUnsignedTransaction({
+        from: SENDER,
+        space: Space.Evm,
+        to: RECIPIENT,
+        gas: 10n ** 12n,
+      }),
+    ).rejects.toThrow("not accepted");
+
+    const txHash = await node.sendUnsignedTransaction({
+      from: SENDER,
+      space: Space.Evm,
+      to: RECIPIENT,
+      value: 1n,
+    });
+    await node.mineUntilReceipt(txHash);
+
+    const stagesOf = (hash: string) =>
+      events.filter((e) => e.hash === hash).map((e) => e.stage);
+    for (
+      let i = 0;
+      i < 50 && !stagesOf(txHash).includes(TransactionStage.Receipt);
+      i++
+    ) {
+      await sleep(100);
+    }
+
+    const rejected = events.find((e) => e.stage === TransactionStage.Rejected);
+    expect(rejected?.from).toBe(SENDER);
+    expect(rejected?.reason).toBeTruthy();
+
+    expect(stagesOf(txHash)).toEqual([
+      TransactionStage.Accepted,
+      TransactionStage.Packed,
+      TransactionStage.Executed,
+      TransactionStage.Receipt,
+    ]);
+    const receipt = events.find(
+      (e) => e.hash === txHash && e.stage === TransactionStage.Receipt,
+    );
+    expect(receipt?.receipt?.outcomeStatus).toBe(OutcomeStatus.Success);
+    expect(events.every((e) => e.from === SENDER)).toBe(true);
+  });
+
+  test("should report eSpace transactions refused through request", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    const events: TransactionEvent[] = [];
+    node.onTransaction((event) => events.push(event));
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    // signed for evmChainId 2222, this node runs the default one
+    await expect(
+      node.request("eth_sendRawTransaction", [EVM_DEPLOY_RAW_TX]),
+    ).rejects.toThrow();
+
+    for (let i = 0; i < 50 && events.length === 0; i++) {
+      await sleep(100);
+    }
+    expect(events).toHaveLength(1);
+    expect(events[0].stage).toBe(TransactionStage.Rejected);
+    expect(events[0].space).toBe(Space.Evm);
+    expect(events[0].reason).toBeTruthy();
+  });
+});
@@ -0,0 +1,85 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, NotReadyReason, Space } from "../index";
+import { getFreePorts } from "./help";
+
+const SENDER = "0x1000000000000000000000000000000000000abc";
+const RECIPIENT = "0x1000000000000000000000000000000000000def";
+
+/**
+ * Test transaction pool inspection
+ * Shows how to find out why a transaction is not packed
+ */
+describe("Transaction Pool", () => {
+  test("should list, drop and clear pool transactions", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+    await node.setBalance(SENDER, Space.Evm, 10n ** 18n);
+    await node.impersonateAccount(SENDER, Space.Evm);
+
+    const send = (nonce: bigint) =>
+      node.sendUnsignedTransaction({
+        from: SENDER,
+        space: Space.Evm,
+        to: RECIPIENT,
+        value: 1n,
+        nonce,
+      });
+    const ready = await send(0n);
+    const gapped = await send(2n);
+
+    const content = await node.getPoolContent(Space.Evm);
+    expect(content.pending.map((tx) => tx.hash)).toEqual([ready]);
+    expect(content.queued).toHaveLength(1);
+    expect(content.queued[0].transaction.hash).toBe(gapped);
+    expect(content.queued[0].reason).toBe(NotReadyReason.NonceGap);
+    expect(await node.getPoolContent(Space.Native)).toEqual({
+      pending: [],
+      queued: [],
+    });
+
+    expect(await node.dropTransaction(ready)).toBe(true);
+    expect(await node.dropTransaction(ready)).toBe(false);
+    expect((await node.getPoolContent()).pending).toEqual([]);
+    expect(await node.getTransaction(gapped)).not.toBeNull();
+
+    await node.clearPool();
+    expect(await node.getTransaction(gapped)).toBeNull();
+  });
+
+  test("should refuse to drop packed transactions", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+    await node.pauseMining();
+    await node.setBalance(SENDER, Space.Evm, 10n ** 18n);
+    await node.impersonateAccount(SENDER, Space.Evm);
+
+    const hash = await node.sendUnsignedTransaction({
+      from: SENDER,
+      space: Space.Evm,
+      to: RECIPIENT,
+      value: 1n,
+      nonce: 0n,
+    });
+    await node.mineUntilReceipt(hash);
+
+    await expect(node.dropTransaction(hash)).rejects.toThrow(/packed/);
+  });
+
+  test("should read the pool limits", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+
+    await using node = new ConfluxNode();
+    await node.startNode({ tcpPort: udpAndTcpPort, udpPort: udpAndTcpPort });
+
+    const limits = await node.getPoolLimits();
+    expect(limits.capacity).toBeGreaterThan(0);
+    expect(limits.maxTransactionGas).toBeGreaterThan(0n);
+    expect(limits.minEvmGasPrice).toBeGreaterThan(0n);
+    expect(limits.unpacked).toBe(0);
+  });
+});
@@ -0,0 +1,56 @@
+import { describe, expect, test } from "vitest";
+import { ConfluxNode, NodeState } from "../index";
+import { getFreePorts } from "./help";
+
+const waitForState = async (
+  node: ConfluxNode,
+  state: NodeState,
+  timeoutMs = 10_000,
+) => {
+  const deadline = Date.now() + timeoutMs;
+  let current = node.getStatus().state;
+  while (current !== state) {
+    if (Date.now() >= deadline) {
+      throw new Error(`Node stayed ${current} instead of becoming ${state}`);
+    }
+    await new Promise((resolve) => setTimeout(resolve, 100));
+    current = node.getStatus().state;
+  }
+};
+
+/**
+ * Test the host-liveness watchdog
+ * Shows how a node stops itself once the host stops calling `heartbeat`
+ */
+describe("Watchdog", () => {
+  test("should keep running while heartbeats arrive", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      watchdogTimeoutMs: 1000,
+    });
+
+    for (let i = 0; i < 6; i++) {
+      node.heartbeat();
+      await new Promise((resolve) => setTimeout(resolve, 250));
+    }
+    expect(node.getStatus().state).toBe(NodeState.Running);
+
+    await node.stopNode();
+  });
+
+  test("should stop the node when heartbeats stop", async () => {
+    const [udpAndTcpPort] = await getFreePorts();
+    const node = new ConfluxNode();
+    await node.startNode({
+      tcpPort: udpAndTcpPort,
+      udpPort: udpAndTcpPort,
+      watchdogTimeoutMs: 500,
+    });
+
+    await waitForState(node, NodeState.Stopped);
+    await expect(node.stopNode()).rejects.toThrow("Node is not running");
+  }, 30000);
+});
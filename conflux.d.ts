@@ -1,9 +1,275 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * A Conflux node that can be started and stopped any number of times.
+ *
+ * `start_node` is only allowed while the node is not running and `stop_node`
+ * only while it is. Every start builds a fresh exit signal and, when no data
+ * directory is configured, a fresh temp directory.
+ */
 export declare class ConfluxNode {
   constructor()
+  /**
+   * The start can be cancelled with `cancel_start` as soon as this returns,
+   * before any of the async work began.
+   */
   startNode(config: ConfluxConfig): Promise<void>
-  stopNode(): Promise<void>
+  /**
+   * Stop the node and boot it again on the same data directory.
+   *
+   * Ports, chain data and the node identity are kept, including an
+   * automatically created temp directory. Fields set in `overrides` replace
+   * the ones the node was started with.
+   */
+  restartNode(overrides?: ConfluxConfig | undefined | null): Promise<RestartResult>
+  /**
+   * Stop the node and resolve with how the shutdown went.
+   *
+   * With `timeout_ms` the call resolves once the deadline passed, even if the
+   * client is still shutting down. The report is `forced` then, and the
+   * client holds on to its ports and data directory until it is done.
+   */
+  stopNode(options?: StopOptions | undefined | null): Promise<ShutdownReport>
+  /**
+   * Stop the node if it is running or starting, and do nothing otherwise.
+   *
+   * Backs `Symbol.asyncDispose`, so it never fails just because the node is
+   * already stopped. Returns `null` if there was nothing to stop.
+   */
+  dispose(): Promise<ShutdownReport | null>
+  /**
+   * Send a JSON-RPC request straight to the node, without going through a port.
+   *
+   * Resolves with the `result` of the call and rejects with the JSON-RPC error.
+   * With automine on, sent raw transactions are packed before it resolves.
+   */
+  request(method: string, params?: any | undefined | null): Promise<any>
+  /**
+   * Send several JSON-RPC requests in order. A failing call doesn't fail the
+   * batch, its response carries the `error` instead.
+   */
+  requestBatch(calls: Array<RpcCall>): Promise<Array<RpcResponse>>
+  /** The current lifecycle state, when it was entered and the last error. */
+  getStatus(): NodeStatus
+  /**
+   * Register a callback that is invoked on every state transition.
+   * Registering a new callback replaces the previous one.
+   */
+  onStateChange(callback: ((arg: StateTransition) => void)): void
+  /**
+   * Register a callback for panics and unexpected exits of the node.
+   * Panics on conflux threads are reported to every node in the process.
+   */
+  onCrash(callback: ((arg: CrashReport) => void)): void
+  /**
+   * Register a callback for `console.log` calls of eSpace contracts on dev
+   * mode nodes. They are also written to the log.
+   * Register it before `startNode`, the calls are only traced when a callback
+   * is set at start. Nodes started from a config file need
+   * `executive_trace = true` in it.
+   * Registering a new callback replaces the previous one.
+   */
+  onConsoleLog(callback: ((arg: ConsoleLog) => void)): void
+  /**
+   * Register a callback for every block inserted into the tree-graph.
+   * Registering a new callback replaces the previous one.
+   */
+  onBlock(callback: ((arg: BlockEvent) => void)): void
+  /**
+   * Register a callback for every executed epoch.
+   * Registering a new callback replaces the previous one.
+   */
+  onEpoch(callback: ((arg: EpochEvent) => void)): void
+  /**
+   * Register a callback that follows transactions of both spaces through the
+   * pool, into blocks and to their receipts. With a `filter`, only
+   * transactions with one of its hashes or senders are reported.
+   *
+   * Rejections are reported for the methods of this class and for
+   * `cfx_sendRawTransaction` and `eth_sendRawTransaction` sent through
+   * `request`. Transactions the RPC ports refuse are not reported, the ports
+   * answer them themselves. Registering a new callback replaces the previous
+   * one.
+   */
+  onTransaction(callback: ((arg: TransactionEvent) => void), filter?: TransactionFilter | undefined | null): void
+  /**
+   * Stream the logs of executed transactions as they are executed, without
+   * RPC filters that expire.
+   *
+   * Logs that a pivot switch reverts are delivered again with `removed` set,
+   * their transactions' logs on the new pivot chain follow.
+   */
+  logs(filter?: LogFilter | undefined | null): Promise<LogStream>
+  /**
+   * Tell the watchdog the host is still alive.
+   *
+   * Only needed when `watchdog_timeout_ms` is configured, the node shuts
+   * itself down once no heartbeat arrived within that interval.
+   */
+  heartbeat(): void
+  /**
+   * Cancel a pending `start_node` or `restart_node`.
+   *
+   * The half-started node is torn down and the pending call fails with a
+   * cancellation error. A cancel right after `start_node` was called also
+   * counts, even if the start did not get to do anything yet. Returns `false`
+   * if no start was in progress.
+   */
+  cancelStart(): boolean
+  getBestEpochNumber(): Promise<bigint>
+  getBlockByHash(hash: string): Promise<BlockInfo | null>
+  /** The pivot block of `epoch`. */
+  getBlockByEpoch(epoch: bigint): Promise<BlockInfo | null>
+  /**
+   * Pivot block hashes from `from_epoch` (default genesis) to `to_epoch`
+   * (default the best epoch), both inclusive.
+   */
+  getPivotChain(fromEpoch?: bigint | undefined | null, toEpoch?: bigint | undefined | null): Promise<Array<string>>
+  /** A packed transaction, or one that is still waiting in the pool. */
+  getTransaction(hash: string): Promise<TransactionInfo | null>
+  /** The receipt of an executed transaction. */
+  getReceipt(hash: string): Promise<ReceiptInfo | null>
+  /**
+   * Balance and nonce of an account in the state of `epoch`, the latest
+   * executed state by default.
+   */
+  getAccount(address: string, space: Space, epoch?: bigint | undefined | null): Promise<AccountInfo>
+  /** How blocks are produced automatically right now. */
+  getMiningMode(): Promise<MiningMode>
+  /** Turn mining on new pool transactions on or off. */
+  setAutomine(enabled: boolean): Promise<void>
+  /** Mine a block every `interval_ms`, `0` turns interval mining off. */
+  setIntervalMining(intervalMs: number): Promise<void>
+  /** Stop automatic block production until `resume_mining` is called. */
+  pauseMining(): Promise<void>
+  resumeMining(): Promise<void>
+  /** Generate `count` empty blocks. */
+  mineBlocks(count: number): Promise<Array<MinedBlock>>
+  /**
+   * Generate `count` blocks that pack up to `max_txs_per_block` transactions
+   * each from the pool.
+   */
+  minePendingBlocks(count: number, maxTxsPerBlock?: number | undefined | null): Promise<Array<MinedBlock>>
+  /**
+   * Generate blocks that pack the pool until the transaction `tx_hash` has a
+   * receipt, including the blocks needed for its epoch to be executed.
+   *
+   * Fails after `max_blocks` (default 100) blocks without a receipt.
+   */
+  mineUntilReceipt(txHash: string, maxBlocks?: number | undefined | null): Promise<Array<MinedBlock>>
+  /**
+   * Generate one block with an explicit parent, referees, timestamp and
+   * transactions. The block does not have to extend the pivot chain.
+   */
+  mineCustomBlock(options: CustomBlockOptions): Promise<MinedBlock>
+  /**
+   * Replace the last `depth` pivot blocks with a competing branch of at least
+   * `depth + 1` empty blocks forked from the pivot block before them. The
+   * branch is extended until it is the pivot chain.
+   *
+   * Automatic mining should be paused, blocks mined on the old branch while
+   * the reorg runs can outweigh the new one.
+   */
+  reorg(depth: number): Promise<ReorgResult>
+  /**
+   * Use `timestamp` (unix seconds) for the next block. Later blocks continue
+   * from there.
+   */
+  setNextBlockTimestamp(timestamp: bigint): Promise<void>
+  /**
+   * Move the time forward by `seconds`. Resolves with how many seconds the
+   * node time is ahead of the wall clock in total.
+   */
+  increaseTime(seconds: bigint): Promise<bigint>
+  /**
+   * Advance the timestamp by exactly `seconds` on every block, independent
+   * of the wall clock. `0` goes back to following the wall clock.
+   */
+  setBlockTimestampInterval(seconds: number): Promise<void>
+  /**
+   * Record the chain, the transaction pool and the node time. Resolves with
+   * an id for `revert`.
+   */
+  snapshot(): Promise<number>
+  /**
+   * Make the state, pool and node time of snapshot `id` current again. The
+   * snapshot and all later ones are used up.
+   *
+   * Nothing is rolled back, a heavier branch of empty blocks is mined on the
+   * snapshot instead. Epoch numbers keep growing and the miner keeps the
+   * rewards of the new blocks.
+   *
+   * Resolves with `false` if there is no such snapshot. Automatic mining
+   * should be paused, blocks mined on the old branch while the revert runs
+   * delay it.
+   */
+  revert(id: number): Promise<boolean>
+  setBalance(address: string, space: Space, balance: bigint): Promise<void>
+  setNonce(address: string, space: Space, nonce: bigint): Promise<void>
+  /** Replace the code of `address` with the hex encoded runtime `code`. */
+  setCode(address: string, space: Space, code: string): Promise<void>
+  /** Write the 32 byte `value` to storage `slot` of `address`, both hex. */
+  setStorageAt(address: string, space: Space, slot: string, value: string): Promise<void>
+  /**
+   * Accept unsigned transactions from `address` until
+   * `stop_impersonating_account` is called.
+   */
+  impersonateAccount(address: string, space: Space): Promise<void>
+  /** Resolves with `false` if `address` was not impersonated. */
+  stopImpersonatingAccount(address: string, space: Space): Promise<boolean>
+  /**
+   * Add a transaction from an impersonated sender to the pool. Resolves with
+   * the transaction hash, with automine on once the transaction is packed.
+   */
+  sendUnsignedTransaction(transaction: UnsignedTransaction): Promise<string>
+  /**
+   * Transactions in the pool, of one space or both. Queued transactions come
+   * with the reason they can't be packed yet.
+   *
+   * Reasons are worked out from the latest executed state, gas sponsorship is
+   * not taken into account.
+   */
+  getPoolContent(space?: Space | undefined | null): Promise<PoolContent>
+  /**
+   * Remove transaction `hash` from the pool. Later transactions of the same
+   * sender stay and wait for the nonce to be filled again.
+   *
+   * Resolves with `false` if the transaction is not in the pool. Transactions
+   * a block packed already can't be dropped.
+   */
+  dropTransaction(hash: string): Promise<boolean>
+  /** Remove every transaction from the pool. */
+  clearPool(): Promise<void>
+  getPoolLimits(): Promise<PoolLimits>
+}
+
+export interface AccountInfo {
+  balance: bigint
+  nonce: bigint
+}
+
+export interface BlockEvent {
+  hash: string
+  height: bigint
+  /** `null` if the block was not part of an epoch yet when it was inserted. */
+  epochNumber?: bigint
+  /** Whether the block was the pivot of its epoch when it was inserted. */
+  pivot: boolean
+  timestamp: bigint
+}
+
+export interface BlockInfo {
+  hash: string
+  parentHash: string
+  height: bigint
+  /** The epoch the block was executed in, `null` if it is not in an epoch yet. */
+  epochNumber?: bigint
+  timestamp: bigint
+  miner: string
+  difficulty: bigint
+  gasLimit: bigint
+  refereeHashes: Array<string>
+  transactions: Array<string>
 }
 
 export interface ConfluxConfig {
@@ -81,6 +347,24 @@ export interface ConfluxConfig {
   genesisSecrets?: Array<string>
   /** The private key of the genesis (eSpace), every account will be receive 10000 CFX */
   genesisEvmSecrets?: Array<string>
+  /**
+   * How long `start_node` may take as a whole. When the deadline passes the
+   * half-started node is torn down and a timeout error is returned.
+   * @default 120000
+   */
+  startupTimeoutMs?: number
+  /**
+   * How long `start_node` waits for the node to become ready: genesis executed,
+   * consensus in `NormalSyncPhase` and every configured RPC port bound.
+   * @default 20000
+   */
+  readyTimeoutMs?: number
+  /**
+   * When set, the node shuts itself down if `heartbeat` was not called for this
+   * many milliseconds, so it doesn't outlive a host that was killed.
+   * The watchdog is armed once the node is ready.
+   */
+  watchdogTimeoutMs?: number
   /**
    * `tcp_port` is the TCP port that the process listens for P2P messages. The default is 32323.
    * @default 32323
@@ -101,6 +385,9 @@ export interface ConfluxConfig {
   publicRpcApis?: string
   /**
    * Possible eSpace names are: eth, ethpubsub, ethdebug.
+   * Dev mode nodes also accept `anvil`, which serves Hardhat and Anvil
+   * compatible `evm_*`, `anvil_*` and `hardhat_*` methods on the eSpace ports.
+   * The ports then only listen on 127.0.0.1.
    *  @default 'evm'
    */
   publicEvmRpcApis?: string
@@ -209,3 +496,342 @@ export interface ConfluxConfig {
   /** if `get_logs_filter_max_limit` is configured but the query would return more logs */
   getLogsFilterMaxLimit?: number
 }
+
+export interface ConsoleLog {
+  transactionHash: string
+  /**
+   * How many calls deep the `console.log` call was made, 1 for calls from
+   * the contract the transaction calls.
+   */
+  depth: number
+  /** The arguments, formatted the way Hardhat prints them. */
+  message: string
+}
+
+export declare enum CrashKind {
+  /** A thread panicked. */
+  Panic = 'Panic',
+  /** The client signalled its exit condvar without `stop_node` being called. */
+  Exit = 'Exit'
+}
+
+export interface CrashReport {
+  kind: CrashKind
+  threadName: string
+  message: string
+  backtrace?: string
+  /** Unix timestamp in milliseconds. */
+  timestamp: number
+}
+
+export interface CustomBlockOptions {
+  parentHash: string
+  /** Blocks referenced besides the parent, e.g. blocks of a competing branch. */
+  refereeHashes?: Array<string>
+  /** Unix timestamp in seconds, the current time by default. */
+  timestamp?: bigint
+  /** Raw signed transactions to pack, in both spaces. The pool is not used. */
+  transactions?: Array<string>
+}
+
+export interface EpochEvent {
+  epochNumber: bigint
+  /** The pivot block of the epoch. */
+  hash: string
+  height: bigint
+  timestamp: bigint
+  /** All blocks of the epoch in execution order, the pivot block last. */
+  blockHashes: Array<string>
+}
+
+/**
+ * An async iterator over the logs of one `logs` call. It ends when the node
+ * stops.
+ */
+export declare class LogStream {
+  [Symbol.asyncIterator](): AsyncGenerator<LogEntry, void, void>
+}
+
+export interface LogEntry {
+  address: string
+  space: Space
+  topics: Array<string>
+  data: string
+  transactionHash: string
+  transactionIndex: number
+  /** The index of the log within its transaction. */
+  logIndex: number
+  blockHash: string
+  epochNumber: bigint
+  /** `true` if a pivot switch reverted the log, it was delivered before. */
+  removed: boolean
+}
+
+export interface LogFilter {
+  /** Both spaces by default. */
+  space?: Space
+  /** Logs of any of these contracts, all contracts by default. */
+  address?: Array<string>
+  /** Topic `i` of a log must be one of `topics[i]`, `null` matches any topic. */
+  topics?: Array<Array<string> | undefined | null>
+  /**
+   * Start with the logs of this executed epoch, the next executed epoch by
+   * default.
+   */
+  fromEpoch?: bigint
+}
+
+export interface LogInfo {
+  address: string
+  space: Space
+  topics: Array<string>
+  data: string
+}
+
+export interface MinedBlock {
+  hash: string
+  /** `null` if the block did not become part of an epoch yet. */
+  epochNumber?: bigint
+}
+
+export interface MiningMode {
+  /**
+   * Mine `DEFERRED_STATE_EPOCH_COUNT` blocks whenever new transactions
+   * become ready to be packed, so they are executed right away. Transactions
+   * waiting for a missing nonce don't trigger it until the gap is filled.
+   *
+   * `request` and `sendUnsignedTransaction` resolve once the transactions
+   * they sent are packed, so does `cfx_sendRawTransaction` on the ports.
+   * The ports answer that way as long as the node started with automine,
+   * `setAutomine` can't change them. eSpace ports answer right away.
+   */
+  automine: boolean
+  /** Mine a block every `interval_ms`. */
+  intervalMs?: number
+  /** Whether automatic block production is paused. Manual mining still works. */
+  paused: boolean
+}
+
+export declare enum NodeState {
+  /** The node has never been started. */
+  Idle = 'Idle',
+  Starting = 'Starting',
+  Running = 'Running',
+  Stopping = 'Stopping',
+  Stopped = 'Stopped',
+  /** The node failed to boot or died while running, see `last_error`. */
+  Crashed = 'Crashed'
+}
+
+export interface NodeStatus {
+  state: NodeState
+  /** Unix timestamp in milliseconds of the last transition. */
+  since: number
+  lastError?: string
+  /** The most recent transitions, oldest first. */
+  transitions: Array<StateTransition>
+}
+
+export declare enum NotReadyReason {
+  /** The nonce is below the sender's nonce, the transaction will be dropped. */
+  StaleNonce = 'StaleNonce',
+  /** A transaction with an earlier nonce of the sender is missing. */
+  NonceGap = 'NonceGap',
+  /** The sender can't pay value and gas of this and its earlier transactions. */
+  NotEnoughCash = 'NotEnoughCash',
+  /** The sender can't pay the collateral for the storage limit. */
+  NotEnoughCashForStorage = 'NotEnoughCashForStorage',
+  GasPriceTooLow = 'GasPriceTooLow',
+  /** The gas limit is above the maximum of a single transaction. */
+  GasLimitTooHigh = 'GasLimitTooHigh',
+  /** None of the above, the pool did not re-evaluate the transaction yet. */
+  Unknown = 'Unknown'
+}
+
+export declare enum OutcomeStatus {
+  Success = 'Success',
+  Failure = 'Failure',
+  Skipped = 'Skipped'
+}
+
+export interface PoolContent {
+  /** Transactions that can be packed into the next block. */
+  pending: Array<TransactionInfo>
+  queued: Array<QueuedTransaction>
+}
+
+export interface PoolLimits {
+  /** The maximum number of transactions in the pool. */
+  capacity: number
+  minNativeGasPrice: bigint
+  minEvmGasPrice: bigint
+  /** The maximum gas limit of a single transaction. */
+  maxTransactionGas: bigint
+  /** Transactions in the pool right now that are not packed yet. */
+  unpacked: number
+}
+
+export interface QueuedTransaction {
+  transaction: TransactionInfo
+  reason: NotReadyReason
+}
+
+export interface ReceiptInfo {
+  transactionHash: string
+  blockHash: string
+  epochNumber: bigint
+  index: number
+  outcomeStatus: OutcomeStatus
+  gasUsed: bigint
+  gasFee: bigint
+  logs: Array<LogInfo>
+}
+
+export interface ReorgResult {
+  /** The pivot blocks that were replaced, oldest first. */
+  removedPivotHashes: Array<string>
+  /** The blocks of the new, heavier branch, oldest first. */
+  newBlocks: Array<MinedBlock>
+}
+
+export interface RestartResult {
+  /** The best epoch number the chain resumed from. */
+  resumedEpoch: number
+}
+
+export interface RpcCall {
+  method: string
+  params?: any
+}
+
+export interface RpcError {
+  code: number
+  message: string
+  data?: any
+}
+
+/** Either `result` or `error` is set, like in a JSON-RPC response. */
+export interface RpcResponse {
+  result?: any
+  error?: RpcError
+}
+
+export interface ShutdownReport {
+  elapsedMs: number
+  /** Whether every database handle was released, so the data was flushed. */
+  dbFlushed: boolean
+  /** The last executed epoch at the time the shutdown started. */
+  lastExecutedEpoch?: number
+  /**
+   * Whether the clean shutdown missed the deadline and the node thread was
+   * abandoned.
+   *
+   * An abandoned client can't be torn down from outside its thread, it keeps
+   * its ports and data directory until the shutdown finishes on its own. A
+   * new start on the same ports or data directory fails until then.
+   */
+  forced: boolean
+}
+
+export declare enum Space {
+  /** The core space, addresses may be given in base32 or hex. */
+  Native = 'Native',
+  /** The eSpace, addresses are hex. */
+  Evm = 'Evm'
+}
+
+export interface StateTransition {
+  state: NodeState
+  /** Unix timestamp in milliseconds. */
+  timestamp: number
+  /** The error that caused this transition, if any. */
+  error?: string
+}
+
+export interface StopOptions {
+  /**
+   * How long to wait for a clean shutdown before the node thread is abandoned.
+   * If not set, `stop_node` waits until the shutdown completes.
+   *
+   * The abandoned thread keeps shutting down in the background, ports and
+   * data directory stay in use until it is done.
+   */
+  timeoutMs?: number
+}
+
+export interface TransactionEvent {
+  hash: string
+  stage: TransactionStage
+  space: Space
+  /** `null` if the sender of a rejected transaction could not be recovered. */
+  from?: string
+  nonce?: bigint
+  reason?: string
+  blockHash?: string
+  epochNumber?: bigint
+  receipt?: ReceiptInfo
+  replacedBy?: string
+}
+
+export interface TransactionFilter {
+  hashes?: Array<string>
+  /**
+   * Hex addresses match the sender in both spaces, base32 addresses only in
+   * the core space.
+   */
+  senders?: Array<string>
+}
+
+export interface TransactionInfo {
+  hash: string
+  space: Space
+  from: string
+  /** `null` for contract creations. */
+  to?: string
+  nonce: bigint
+  value: bigint
+  gas: bigint
+  gasPrice: bigint
+  data: string
+  /** Location of the transaction, all `null` while it is only in the pool. */
+  blockHash?: string
+  index?: number
+}
+
+export declare enum TransactionStage {
+  /** The transaction entered the pool. */
+  Accepted = 'Accepted',
+  /** The pool refused the transaction, see `reason`. */
+  Rejected = 'Rejected',
+  /** The transaction was packed into `block_hash`. */
+  Packed = 'Packed',
+  /** The epoch executing the transaction was executed. */
+  Executed = 'Executed',
+  /** The receipt is available. */
+  Receipt = 'Receipt',
+  /** The transaction left the pool without being packed. */
+  Dropped = 'Dropped',
+  /**
+   * A transaction with the same sender and nonce took its place, see
+   * `replaced_by`.
+   */
+  Replaced = 'Replaced'
+}
+
+export interface UnsignedTransaction {
+  /** An impersonated address. */
+  from: string
+  space: Space
+  /** `null` deploys a contract. */
+  to?: string
+  value?: bigint
+  data?: string
+  /** 1,000,000 by default. */
+  gas?: bigint
+  /** 20 GDrip by default. */
+  gasPrice?: bigint
+  /** The next nonce of the sender in the pool by default. */
+  nonce?: bigint
+  /** Core space only, 10,000 bytes by default. */
+  storageLimit?: bigint
+}
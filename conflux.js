@@ -509,3 +509,10 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.ConfluxNode = nativeBinding.ConfluxNode
+module.exports.LogStream = nativeBinding.LogStream
+module.exports.CrashKind = nativeBinding.CrashKind
+module.exports.NodeState = nativeBinding.NodeState
+module.exports.NotReadyReason = nativeBinding.NotReadyReason
+module.exports.OutcomeStatus = nativeBinding.OutcomeStatus
+module.exports.Space = nativeBinding.Space
+module.exports.TransactionStage = nativeBinding.TransactionStage
@@ -1,7 +1,45 @@
 import { ConfluxInstance } from "./lib/conflux-instance";
 import type { Config } from "./lib/types";
 
-export { ConfluxConfig } from "./conflux";
+export {
+  AccountInfo,
+  BlockEvent,
+  BlockInfo,
+  ConfluxConfig,
+  ConsoleLog,
+  CrashKind,
+  CrashReport,
+  CustomBlockOptions,
+  EpochEvent,
+  LogEntry,
+  LogFilter,
+  LogInfo,
+  LogStream,
+  MinedBlock,
+  MiningMode,
+  NodeState,
+  NodeStatus,
+  NotReadyReason,
+  OutcomeStatus,
+  PoolContent,
+  PoolLimits,
+  QueuedTransaction,
+  ReceiptInfo,
+  ReorgResult,
+  RpcCall,
+  RpcError,
+  RpcResponse,
+  ShutdownReport,
+  Space,
+  StateTransition,
+  StopOptions,
+  TransactionEvent,
+  TransactionFilter,
+  TransactionInfo,
+  TransactionStage,
+  UnsignedTransaction,
+} from "./conflux";
+export { ConfluxNode, StartNodeOptions } from "./lib/node";
 export { Config } from "./lib/types";
 
 export interface CreateServerReturnType {
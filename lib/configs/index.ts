@@ -12,6 +12,7 @@ export const buildConfig = (config: Config): ConfluxConfig => {
       "./pos_config/initial_nodes.json",
     ),
     logConf: finalConfig.log ? path.join(__dirname, "./log.yaml") : undefined,
+    readyTimeoutMs: finalConfig.timeout,
     ...config,
   };
 };
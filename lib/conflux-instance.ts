@@ -2,30 +2,31 @@ import { type ChildProcess, fork } from "node:child_process";
 import path from "node:path";
 import type { ConfluxConfig } from "../conflux";
 import { buildConfig } from "./configs";
-import { waitForNodeRPCReady } from "./sync";
 import {
   type Config,
   DEFAULT_CONFIG,
   type MessageFromWorker,
   type MessageToWorker,
-  type NodeRequestOptions,
   type WorkerEvents,
 } from "./types";
 
+const STOP_KILL_GRACE_MS = 5000;
+const HEARTBEATS_PER_WATCHDOG_TIMEOUT = 5;
+
 export class ConfluxInstance {
   private isServiceStarted = false;
   private readonly config: ConfluxConfig;
-  private readonly timeout: number;
-  private readonly retryInterval: number;
   private worker: ChildProcess | null = null;
+  private heartbeatTimer: NodeJS.Timeout | null = null;
   private events: WorkerEvents;
   private readonly stopTimeout: number;
 
   constructor(config: Config) {
-    this.timeout = config.timeout || DEFAULT_CONFIG.timeout;
-    this.retryInterval = config.retryInterval || DEFAULT_CONFIG.retryInterval;
     this.stopTimeout = config.timeout || DEFAULT_CONFIG.timeout;
 
+    // The worker stops its node when the IPC channel to the parent closes.
+    // `watchdogTimeoutMs` additionally stops it when the parent is alive but
+    // stops sending heartbeats, it is off unless configured.
     this.config = buildConfig(config);
 
     this.events = {
@@ -86,11 +87,10 @@ export class ConfluxInstance {
       };
 
       const handleStartMessage = (message: MessageFromWorker) => {
+        // the worker reports "started" once the node can serve requests
         if (message.type === "started") {
           this.events.onStart?.();
-          this.waitForRPCReady()
-            .then(() => finish())
-            .catch(finish);
+          finish();
           return;
         }
         if (message.type === "error") {
@@ -124,6 +124,7 @@ export class ConfluxInstance {
       this.worker.on("exit", handleExit);
 
       this.worker.send(startMessage);
+      this.startHeartbeat();
     });
   };
 
@@ -139,12 +140,14 @@ export class ConfluxInstance {
     return new Promise<void>((resolve, reject) => {
       let settled = false;
       let stopped = false;
+      // The node gives up on a clean shutdown after `stopTimeout`, only kill the
+      // worker if it doesn't exit shortly after that.
       const stopTimer = setTimeout(() => {
         if (settled) return;
         if (!worker.killed && worker.exitCode === null) {
           worker.kill();
         }
-      }, this.stopTimeout);
+      }, this.stopTimeout + STOP_KILL_GRACE_MS);
 
       const cleanupStopListeners = () => {
         clearTimeout(stopTimer);
@@ -203,26 +206,37 @@ export class ConfluxInstance {
       worker.on("message", handleMessage);
       worker.on("error", handleError);
       worker.on("exit", handleExit);
-      worker.send({ type: "stop" } as MessageToWorker);
+      const stopMessage: MessageToWorker = {
+        type: "stop",
+        timeoutMs: this.stopTimeout,
+      };
+      worker.send(stopMessage);
     });
   };
 
-  private waitForRPCReady = async (): Promise<void> => {
-    if (!this.config.jsonrpcHttpPort && !this.config.jsonrpcWsPort) {
-      return;
-    }
-
-    const rpcConfig: NodeRequestOptions = {
-      httpPort: this.config.jsonrpcHttpPort,
-      wsPort: this.config.jsonrpcWsPort,
-      timeout: this.timeout,
-      retryInterval: this.retryInterval,
-    };
-
-    await waitForNodeRPCReady(rpcConfig);
+  private startHeartbeat = () => {
+    const { watchdogTimeoutMs } = this.config;
+    if (!watchdogTimeoutMs) return;
+
+    const heartbeat: MessageToWorker = { type: "heartbeat" };
+    const interval = Math.max(
+      1,
+      Math.floor(watchdogTimeoutMs / HEARTBEATS_PER_WATCHDOG_TIMEOUT),
+    );
+    this.heartbeatTimer = setInterval(() => {
+      if (this.worker?.connected) {
+        this.worker.send(heartbeat);
+      }
+    }, interval);
+    // Heartbeats alone must not keep the parent process alive.
+    this.heartbeatTimer.unref();
   };
 
   private cleanup = () => {
+    if (this.heartbeatTimer) {
+      clearInterval(this.heartbeatTimer);
+      this.heartbeatTimer = null;
+    }
     if (this.worker) {
       this.worker.removeAllListeners();
       if (!this.worker.killed) {
@@ -0,0 +1,36 @@
+import { ConfluxNode as NativeConfluxNode } from "../conflux";
+import { buildConfig } from "./configs";
+import type { Config } from "./types";
+
+export interface StartNodeOptions {
+  /** Aborting the signal tears down the half-started node and rejects `startNode` */
+  signal?: AbortSignal;
+}
+
+/**
+ * In-process Conflux node.
+ * Several instances can run side by side in one Node.js process as long as they
+ * use different ports and data directories.
+ */
+export class ConfluxNode extends NativeConfluxNode {
+  async startNode(
+    config: Config = {},
+    options: StartNodeOptions = {},
+  ): Promise<void> {
+    const { signal } = options;
+    signal?.throwIfAborted();
+
+    const onAbort = () => this.cancelStart();
+    signal?.addEventListener("abort", onAbort, { once: true });
+    try {
+      await super.startNode(buildConfig(config));
+    } finally {
+      signal?.removeEventListener("abort", onAbort);
+    }
+  }
+
+  /** Stops the node when an `await using` scope ends */
+  async [Symbol.asyncDispose](): Promise<void> {
+    await this.dispose();
+  }
+}
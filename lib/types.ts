@@ -3,23 +3,18 @@ import type { ConfluxConfig } from "../conflux";
 export interface Config extends ConfluxConfig {
   /** Whether to show conflux node logs */
   log?: boolean;
-  /** Timeout in milliseconds */
+  /** Timeout in milliseconds, used as `readyTimeoutMs` unless that is set */
   timeout?: number;
-  /** Retry interval in milliseconds */
+  /**
+   * Retry interval in milliseconds
+   * @deprecated readiness is checked by the native node, this option is ignored
+   */
   retryInterval?: number;
 }
 
-export interface NodeRequestOptions {
-  httpPort?: number;
-  wsPort?: number;
-  timeout: number;
-  retryInterval: number;
-}
-
 // Default configuration
 export const DEFAULT_CONFIG = {
   timeout: 20000,
-  retryInterval: 300,
   log: false,
 };
 
@@ -30,9 +25,18 @@ export interface StartWorkerMessage {
 
 export interface StopWorkerMessage {
   type: "stop";
+  /** Deadline for a clean shutdown of the node */
+  timeoutMs?: number;
+}
+
+export interface HeartbeatWorkerMessage {
+  type: "heartbeat";
 }
 
-export type MessageToWorker = StartWorkerMessage | StopWorkerMessage;
+export type MessageToWorker =
+  | StartWorkerMessage
+  | StopWorkerMessage
+  | HeartbeatWorkerMessage;
 
 export interface StartedMainMessage {
   type: "started";
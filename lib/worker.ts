@@ -1,4 +1,9 @@
-import { type ConfluxConfig, ConfluxNode } from "../conflux";
+import {
+  type ConfluxConfig,
+  ConfluxNode,
+  NodeState,
+  type StateTransition,
+} from "../conflux";
 import type { MessageFromWorker, MessageToWorker } from "./types";
 
 const isObject = (value: unknown): value is Record<string, unknown> =>
@@ -9,6 +14,11 @@ const isStartMessage = (
 ): message is Extract<MessageToWorker, { type: "start" }> =>
   isObject(message) && message.type === "start" && "config" in message;
 
+const isHeartbeatMessage = (
+  message: unknown,
+): message is Extract<MessageToWorker, { type: "heartbeat" }> =>
+  isObject(message) && message.type === "heartbeat";
+
 const isStopMessage = (
   message: unknown,
 ): message is Extract<MessageToWorker, { type: "stop" }> =>
@@ -17,6 +27,7 @@ const isStopMessage = (
 class WorkerManager {
   private node: ConfluxNode | null = null;
   private isStarted = false;
+  private isStopping = false;
 
   constructor() {
     this.setupMessageHandlers();
@@ -26,6 +37,8 @@ class WorkerManager {
 
   private setupMessageHandlers = () => {
     process.on("message", this.handleMessage);
+    // The parent went away without stopping the node, e.g. it was SIGKILLed.
+    process.on("disconnect", this.handleDisconnect);
   };
 
   private setupErrorHandlers = () => {
@@ -51,8 +64,13 @@ class WorkerManager {
       return;
     }
 
+    if (isHeartbeatMessage(message)) {
+      this.node?.heartbeat();
+      return;
+    }
+
     if (isStopMessage(message)) {
-      this.handleStop();
+      this.handleStop(message.timeoutMs);
       return;
     }
 
@@ -70,6 +88,7 @@ class WorkerManager {
     try {
       if (!this.node) {
         this.node = new ConfluxNode();
+        this.node.onStateChange(this.handleStateChange);
       }
 
       await this.node.startNode(config);
@@ -80,7 +99,8 @@ class WorkerManager {
     }
   };
 
-  private handleStop = async () => {
+  private handleStop = async (timeoutMs?: number) => {
+    this.isStopping = true;
     try {
       if (!this.node) {
         this.sendMessage("stopped");
@@ -88,9 +108,8 @@ class WorkerManager {
         return;
       }
 
-      await this.node.stopNode();
+      await this.node.stopNode({ timeoutMs });
       this.isStarted = false;
-      this.node = null;
       this.sendMessage("stopped");
       this.exit(0);
     } catch (error) {
@@ -99,6 +118,27 @@ class WorkerManager {
     }
   };
 
+  private handleDisconnect = async () => {
+    if (this.isStopping) return;
+    this.isStopping = true;
+    try {
+      await this.node?.dispose();
+    } finally {
+      this.exit(0);
+    }
+  };
+
+  /** A started node that stops without a stop message was stopped by its watchdog */
+  private handleStateChange = (transition: StateTransition) => {
+    if (
+      transition.state === NodeState.Stopped &&
+      this.isStarted &&
+      !this.isStopping
+    ) {
+      this.exit(0);
+    }
+  };
+
   private handleError = (error: unknown) => {
     const normalizedError =
       error instanceof Error ? error : new Error(String(error));
@@ -0,0 +1,374 @@
+use crate::{
+  cheats::{modify_account, parse_bytes, parse_word, set_code, set_storage},
+  clock::{best_timestamp, Clock},
+  components::NodeComponents,
+  error::{NodeError, Result},
+  impersonation::{Impersonation, UnsignedTransaction},
+  mining::{BlockProducer, DEFAULT_TXS_PER_BLOCK},
+  query::{parse_address, to_bigint, Space},
+  rpc::{block_producer, cheat_module, internal_error, invalid_params, RpcResult, RpcSettings},
+  snapshot::Snapshots,
+};
+use cfx_config::Configuration;
+use cfx_tasks::TaskManager;
+use cfx_types::U256;
+use jsonrpsee::{
+  server::{Server, ServerHandle},
+  types::Params,
+  RpcModule,
+};
+use log::info;
+use napi::tokio::runtime::Handle;
+use serde_json::Value;
+use std::{
+  net::{Ipv4Addr, SocketAddr},
+  sync::Arc,
+};
+
+/// The name that selects the dev methods in `public_evm_rpc_apis`.
+pub const ANVIL_API: &str = "anvil";
+
+/// The eSpace ports, taken from the client when the dev methods are selected.
+/// They are served here instead, with the eSpace API, the dev methods and the
+/// `xcfx_*` methods side by side.
+pub struct AnvilPorts {
+  http: Option<u16>,
+  ws: Option<u16>,
+}
+
+impl AnvilPorts {
+  pub fn take(conf: &mut Configuration, enabled: bool) -> Option<Self> {
+    if !enabled || !conf.is_dev_mode() {
+      return None;
+    }
+    let raw_conf = &mut conf.raw_conf;
+    Some(AnvilPorts {
+      http: raw_conf.jsonrpc_http_eth_port.take(),
+      ws: raw_conf.jsonrpc_ws_eth_port.take(),
+    })
+  }
+}
+
+/// What the dev methods act on, shared with the napi methods of the same run.
+#[derive(Clone)]
+pub struct DevContext {
+  pub components: NodeComponents,
+  pub clock: Arc<Clock>,
+  pub snapshots: Arc<Snapshots>,
+  pub impersonation: Arc<Impersonation>,
+}
+
+/// Hardhat and Anvil compatible JSON-RPC servers on the eSpace ports.
+pub struct AnvilServer {
+  handles: Vec<ServerHandle>,
+  // Tasks spawned by the eSpace modules stop with the manager.
+  _task_manager: TaskManager,
+}
+
+impl AnvilServer {
+  pub async fn start(
+    ports: AnvilPorts,
+    settings: &RpcSettings,
+    context: DevContext,
+  ) -> Result<Self> {
+    let task_manager = TaskManager::new(Handle::current());
+    let eth_modules = settings.eth_modules(
+      &context.components,
+      &task_manager,
+      ports.http.is_some(),
+      ports.ws.is_some(),
+    )?;
+    let (http_module, ws_module) = match eth_modules {
+      Some(modules) => (modules.http, modules.ws),
+      None => (None, None),
+    };
+
+    let mut handles = Vec::new();
+    for (port, eth_module) in [(ports.http, http_module), (ports.ws, ws_module)] {
+      let Some(port) = port else {
+        continue;
+      };
+
+      let mut module = eth_module.unwrap_or_else(|| RpcModule::new(()));
+      // Replaced by the variant for impersonated senders.
+      module.remove_method("eth_sendTransaction");
+      let merge = |e| NodeError::Initialization(format!("Failed to merge RPC modules: {}", e));
+      module
+        .merge(anvil_module(context.clone())?)
+        .map_err(merge)?;
+      module
+        .merge(cheat_module(context.clone())?)
+        .map_err(merge)?;
+
+      let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+      let server = Server::builder().build(address).await.map_err(|e| {
+        NodeError::Initialization(format!("Failed to listen on port {}: {}", port, e))
+      })?;
+      handles.push(server.start(module));
+      info!("eSpace JSON-RPC with dev methods listening on {}", address);
+    }
+
+    Ok(AnvilServer {
+      handles,
+      _task_manager: task_manager,
+    })
+  }
+}
+
+impl Drop for AnvilServer {
+  fn drop(&mut self) {
+    for handle in &self.handles {
+      let _ = handle.stop();
+    }
+  }
+}
+
+/// The dev methods, eSpace addresses only. Every method is available under
+/// both its Hardhat and its Anvil name where both exist.
+fn anvil_module(context: DevContext) -> Result<RpcModule<DevContext>> {
+  let mut module = RpcModule::new(context);
+  let register = |e| NodeError::Initialization(format!("Failed to register RPC method: {}", e));
+
+  // Like automine, mines the blocks that pack and execute the pool.
+  module
+    .register_blocking_method("evm_mine", |params, context, _| {
+      let timestamp: Option<Value> = params.sequence().optional_next()?;
+      if let Some(timestamp) = parse_number(timestamp.as_ref())? {
+        let consensus = context.components.consensus().map_err(internal_error)?;
+        context
+          .clock
+          .set_next(&consensus, timestamp)
+          .map_err(invalid_params)?;
+      }
+      BlockProducer::new(&context.components, context.clock.clone())
+        .and_then(|producer| producer.mine_and_execute())
+        .map_err(internal_error)?;
+      RpcResult::Ok("0x0")
+    })
+    .map_err(register)?;
+
+  for name in ["anvil_mine", "hardhat_mine"] {
+    module
+      .register_blocking_method(name, |params, context, _| {
+        let mut params = params.sequence();
+        let count: Option<Value> = params.optional_next()?;
+        let interval: Option<Value> = params.optional_next()?;
+        let interval = parse_number(interval.as_ref())?.unwrap_or_default();
+        let producer =
+          BlockProducer::new(&context.components, context.clock.clone()).map_err(internal_error)?;
+        for i in 0..parse_number(count.as_ref())?.unwrap_or(1) {
+          // The first block keeps the node time, later ones are `interval`
+          // seconds apart.
+          if i > 0 && interval > 0 {
+            let consensus = producer.consensus();
+            context
+              .clock
+              .set_next(consensus, best_timestamp(consensus) + interval)
+              .map_err(internal_error)?;
+          }
+          producer
+            .mine(DEFAULT_TXS_PER_BLOCK)
+            .map_err(internal_error)?;
+        }
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  module
+    .register_method("evm_snapshot", |_, context, _| {
+      let id = context
+        .snapshots
+        .take_snapshot(&context.components, &context.clock)
+        .map_err(internal_error)?;
+      RpcResult::Ok(format!("{:#x}", id))
+    })
+    .map_err(register)?;
+
+  module
+    .register_blocking_method("evm_revert", |params, context, _| {
+      let id: Value = params.one()?;
+      let id = parse_number(Some(&id))?.unwrap_or_default();
+      let Ok(id) = u32::try_from(id) else {
+        return Ok(false);
+      };
+      context
+        .snapshots
+        .revert(&context.components, context.clock.clone(), id)
+        .map_err(internal_error)
+    })
+    .map_err(register)?;
+
+  module
+    .register_method("evm_increaseTime", |params, context, _| {
+      let seconds: Value = params.one()?;
+      let seconds = parse_number(Some(&seconds))?.unwrap_or_default();
+      RpcResult::Ok(context.clock.increase(seconds))
+    })
+    .map_err(register)?;
+
+  module
+    .register_method("evm_setNextBlockTimestamp", |params, context, _| {
+      let timestamp: Value = params.one()?;
+      let timestamp = parse_number(Some(&timestamp))?.unwrap_or_default();
+      let consensus = context.components.consensus().map_err(internal_error)?;
+      context
+        .clock
+        .set_next(&consensus, timestamp)
+        .map_err(invalid_params)?;
+      RpcResult::Ok(true)
+    })
+    .map_err(register)?;
+
+  for name in ["anvil_setBalance", "hardhat_setBalance"] {
+    module
+      .register_blocking_method(name, |params, context, _| {
+        let mut params = params.sequence();
+        let address: String = params.next()?;
+        let balance: U256 = params.next()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        modify_account(&block_producer(&context)?, &address, |account| {
+          account.balance = balance
+        })
+        .map_err(internal_error)?;
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  for name in ["anvil_setNonce", "hardhat_setNonce"] {
+    module
+      .register_blocking_method(name, |params, context, _| {
+        let mut params = params.sequence();
+        let address: String = params.next()?;
+        let nonce: U256 = params.next()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        modify_account(&block_producer(&context)?, &address, |account| {
+          account.nonce = nonce
+        })
+        .map_err(internal_error)?;
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  for name in ["anvil_setCode", "hardhat_setCode"] {
+    module
+      .register_blocking_method(name, |params, context, _| {
+        let mut params = params.sequence();
+        let address: String = params.next()?;
+        let code: String = params.next()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        let code = parse_bytes(&code).map_err(invalid_params)?;
+        set_code(&block_producer(&context)?, &address, code).map_err(internal_error)?;
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  for name in ["anvil_setStorageAt", "hardhat_setStorageAt"] {
+    module
+      .register_blocking_method(name, |params, context, _| {
+        let mut params = params.sequence();
+        let address: String = params.next()?;
+        let slot: String = params.next()?;
+        let value: String = params.next()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        let slot = parse_word(&slot).map_err(invalid_params)?;
+        let value = parse_word(&value).map_err(invalid_params)?;
+        set_storage(
+          &block_producer(&context)?,
+          &address,
+          slot,
+          U256::from_big_endian(value.as_bytes()),
+        )
+        .map_err(internal_error)?;
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  for name in ["anvil_impersonateAccount", "hardhat_impersonateAccount"] {
+    module
+      .register_method(name, |params, context, _| {
+        let address: String = params.one()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        context.impersonation.start(address);
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  for name in [
+    "anvil_stopImpersonatingAccount",
+    "hardhat_stopImpersonatingAccount",
+  ] {
+    module
+      .register_method(name, |params, context, _| {
+        let address: String = params.one()?;
+        let address = parse_address(&address, Space::Evm).map_err(invalid_params)?;
+        context.impersonation.stop(&address);
+        RpcResult::Ok(true)
+      })
+      .map_err(register)?;
+  }
+
+  module
+    .register_method("eth_sendTransaction", |params, context, _| {
+      let transaction = unsigned_transaction(&params)?;
+      context
+        .impersonation
+        .send(&context.components, transaction)
+        .map_err(invalid_params)
+    })
+    .map_err(register)?;
+
+  Ok(module)
+}
+
+/// A number given as JSON number or hex string.
+fn parse_number(value: Option<&Value>) -> RpcResult<Option<u64>> {
+  match value {
+    None | Some(Value::Null) | Some(Value::Object(_)) => Ok(None),
+    Some(Value::Number(number)) => number
+      .as_u64()
+      .map(Some)
+      .ok_or_else(|| invalid_params(format!("invalid number {}", number))),
+    Some(Value::String(hex)) => u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+      .map(Some)
+      .map_err(|_| invalid_params(format!("invalid hex number {}", hex))),
+    Some(other) => Err(invalid_params(format!("invalid number {}", other))),
+  }
+}
+
+/// The `eth_sendTransaction` request object.
+fn unsigned_transaction(params: &Params) -> RpcResult<UnsignedTransaction> {
+  let request: Value = params.one()?;
+  let field = |name: &str| {
+    request
+      .get(name)
+      .and_then(Value::as_str)
+      .map(str::to_string)
+  };
+  let quantity = |name: &str| -> RpcResult<_> {
+    field(name)
+      .map(|value| {
+        U256::from_str_radix(value.trim_start_matches("0x"), 16)
+          .map(|value| to_bigint(&value))
+          .map_err(|_| invalid_params(format!("invalid {} {}", name, value)))
+      })
+      .transpose()
+  };
+
+  Ok(UnsignedTransaction {
+    from: field("from").ok_or_else(|| invalid_params("from is required"))?,
+    space: Space::Evm,
+    to: field("to"),
+    value: quantity("value")?,
+    data: field("data").or_else(|| field("input")),
+    gas: quantity("gas")?,
+    gas_price: quantity("gasPrice")?,
+    nonce: quantity("nonce")?,
+    storage_limit: None,
+  })
+}
@@ -0,0 +1,61 @@
+use log::error;
+use parking_lot::{Condvar, Mutex};
+use std::{thread, time::Duration};
+
+/// A thread running a loop next to the node, until it is stopped.
+///
+/// The loop sleeps in `wait`, which returns early when the thread is woken up
+/// or stopped.
+#[derive(Default)]
+pub struct BackgroundThread {
+  signals: Mutex<Signals>,
+  changed: Condvar,
+  handle: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+#[derive(Default)]
+struct Signals {
+  stopped: bool,
+  woken: bool,
+}
+
+impl BackgroundThread {
+  pub fn spawn(&self, name: &str, f: impl FnOnce() + Send + 'static) {
+    match thread::Builder::new().name(name.into()).spawn(f) {
+      Ok(handle) => *self.handle.lock() = Some(handle),
+      Err(e) => error!("Failed to spawn the {} thread: {}", name, e),
+    }
+  }
+
+  /// Sleep for `timeout`, or until woken up if `None`. Returns `false` once
+  /// the thread is stopped and the loop has to end.
+  pub fn wait(&self, timeout: Option<Duration>) -> bool {
+    let mut signals = self.signals.lock();
+    if !signals.stopped && !signals.woken {
+      match timeout {
+        Some(timeout) => {
+          self.changed.wait_for(&mut signals, timeout);
+        }
+        None => self.changed.wait(&mut signals),
+      }
+    }
+    signals.woken = false;
+    !signals.stopped
+  }
+
+  /// End the current `wait` early, or the next one if the loop is busy.
+  pub fn wake(&self) {
+    self.signals.lock().woken = true;
+    self.changed.notify_all();
+  }
+
+  /// Stop the loop and wait for the iteration in progress to finish.
+  pub fn stop(&self) {
+    self.signals.lock().stopped = true;
+    self.changed.notify_all();
+
+    if let Some(handle) = self.handle.lock().take() {
+      let _ = handle.join();
+    }
+  }
+}
@@ -0,0 +1,41 @@
+use napi::{
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+  Status,
+};
+use parking_lot::Mutex;
+
+/// A JS callback that can be invoked from any thread.
+///
+/// The function is weak, a registered callback doesn't keep the Node.js event
+/// loop alive on its own.
+pub type JsCallback<T> = ThreadsafeFunction<T, (), T, Status, false, true>;
+
+/// Slot for an optional JS callback that can be replaced at any time.
+pub struct CallbackSlot<T: 'static> {
+  callback: Mutex<Option<JsCallback<T>>>,
+}
+
+impl<T: 'static> Default for CallbackSlot<T> {
+  fn default() -> Self {
+    CallbackSlot {
+      callback: Mutex::new(None),
+    }
+  }
+}
+
+impl<T: 'static> CallbackSlot<T> {
+  pub fn set(&self, callback: JsCallback<T>) {
+    *self.callback.lock() = Some(callback);
+  }
+
+  pub fn is_set(&self) -> bool {
+    self.callback.lock().is_some()
+  }
+
+  /// Queue `value` for the JS callback, does nothing if none is registered.
+  pub fn notify(&self, value: T) {
+    if let Some(callback) = self.callback.lock().as_ref() {
+      callback.call(value, ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+}
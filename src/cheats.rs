@@ -0,0 +1,238 @@
+use crate::{
+  error::{NodeError, Result},
+  mining::{run_blocking, BlockProducer},
+  query::{parse_address, to_u256, Space},
+  ConfluxNode,
+};
+use cfx_parameters::consensus::DEFERRED_STATE_EPOCH_COUNT;
+use cfx_statedb::StateDb;
+use cfx_storage::StateIndex;
+use cfx_types::{AddressWithSpace, H256, U256};
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use primitives::{
+  receipt::BlockReceipts, Account, BlockHeaderBuilder, CodeInfo, StorageKey, StorageValue,
+};
+use rustc_hex::FromHex;
+use std::{str::FromStr, sync::Arc};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Direct writes to account state for setting up test fixtures.
+///
+/// Only available on dev mode nodes. A change is written on top of the latest
+/// executed state as the state of a new empty block, and the node mines the
+/// blocks that make it the latest state right away. That block pays out no
+/// rewards.
+#[napi]
+impl ConfluxNode {
+  #[napi]
+  pub async fn set_balance(&self, address: String, space: Space, balance: BigInt) -> Result<()> {
+    let address = parse_address(&address, space)?;
+    let balance = to_u256(&balance)?;
+    self
+      .cheat(move |producer| {
+        modify_account(producer, &address, |account| account.balance = balance)
+      })
+      .await
+  }
+
+  #[napi]
+  pub async fn set_nonce(&self, address: String, space: Space, nonce: BigInt) -> Result<()> {
+    let address = parse_address(&address, space)?;
+    let nonce = to_u256(&nonce)?;
+    self
+      .cheat(move |producer| modify_account(producer, &address, |account| account.nonce = nonce))
+      .await
+  }
+
+  /// Replace the code of `address` with the hex encoded runtime `code`.
+  #[napi]
+  pub async fn set_code(&self, address: String, space: Space, code: String) -> Result<()> {
+    let address = parse_address(&address, space)?;
+    let code = parse_bytes(&code)?;
+    self
+      .cheat(move |producer| set_code(producer, &address, code))
+      .await
+  }
+
+  /// Write the 32 byte `value` to storage `slot` of `address`, both hex.
+  #[napi]
+  pub async fn set_storage_at(
+    &self,
+    address: String,
+    space: Space,
+    slot: String,
+    value: String,
+  ) -> Result<()> {
+    let address = parse_address(&address, space)?;
+    let slot = parse_word(&slot)?;
+    let value = U256::from_big_endian(parse_word(&value)?.as_bytes());
+    self
+      .cheat(move |producer| set_storage(producer, &address, slot, value))
+      .await
+  }
+}
+
+impl ConfluxNode {
+  async fn cheat(
+    &self,
+    f: impl FnOnce(&BlockProducer) -> Result<()> + Send + 'static,
+  ) -> Result<()> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    run_blocking(move || f(&BlockProducer::new(&components, clock)?)).await
+  }
+}
+
+/// Change an account in the latest state, creating it if needed.
+pub fn modify_account(
+  producer: &BlockProducer,
+  address: &AddressWithSpace,
+  f: impl FnOnce(&mut Account),
+) -> Result<()> {
+  modify_state(producer, |state_db| {
+    let mut account = state_db
+      .get_account(address)?
+      .unwrap_or_else(|| Account::new_empty(address));
+    f(&mut account);
+    state_db.set(account_key(address), &account, None)
+  })
+}
+
+pub fn set_code(producer: &BlockProducer, address: &AddressWithSpace, code: Vec<u8>) -> Result<()> {
+  let code_hash = keccak(&code);
+  modify_state(producer, |state_db| {
+    let mut account = state_db
+      .get_account(address)?
+      .unwrap_or_else(|| Account::new_empty(address));
+    account.code_hash = code_hash;
+    state_db.set(account_key(address), &account, None)?;
+
+    let code = CodeInfo {
+      code: Arc::new(code),
+      owner: address.address,
+    };
+    let key = StorageKey::new_code_key(&address.address, &code_hash).with_space(address.space);
+    state_db.set(key, &code, None)
+  })
+}
+
+pub fn set_storage(
+  producer: &BlockProducer,
+  address: &AddressWithSpace,
+  slot: H256,
+  value: U256,
+) -> Result<()> {
+  modify_state(producer, |state_db| {
+    if state_db.get_account(address)?.is_none() {
+      state_db.set(account_key(address), &Account::new_empty(address), None)?;
+    }
+    let key =
+      StorageKey::new_storage_key(&address.address, slot.as_bytes()).with_space(address.space);
+    state_db.set(key, &StorageValue { value, owner: None }, None)
+  })
+}
+
+/// Apply `f` on top of the latest executed state and commit the result as the
+/// state of a new empty block, which no state was committed for yet.
+///
+/// The block is mined on the best block and the blocks that follow it make the
+/// best epoch due, so it is executed once they are in. The new block's epoch
+/// gets the modified state instead of being executed, it pays out no block
+/// rewards. One more block commits to its state and makes it the latest one.
+fn modify_state(
+  producer: &BlockProducer,
+  f: impl FnOnce(&mut StateDb) -> cfx_statedb::Result<()>,
+) -> Result<()> {
+  let failed =
+    |e: &dyn std::fmt::Display| NodeError::Runtime(format!("Failed to modify state: {}", e));
+
+  let consensus = producer.consensus();
+  let base_epoch = consensus.best_epoch_number();
+  let base_hash = consensus.best_block_hash();
+  let block_hash = producer.generate(base_hash, vec![], None, vec![])?;
+  producer.mine_empty(DEFERRED_STATE_EPOCH_COUNT - 1)?;
+  if !producer.wait_until_executed(&base_hash) {
+    return Err(failed(&format!("epoch {} is not executed", base_epoch)));
+  }
+
+  let data_man = consensus.data_manager();
+  let commitment = data_man
+    .get_epoch_execution_commitment(&base_hash)
+    .ok_or_else(|| failed(&format!("epoch {} has no execution commitment", base_epoch)))?;
+  let state_index = StateIndex::new_for_next_epoch(
+    &base_hash,
+    &commitment.state_root_with_aux_info,
+    base_epoch,
+    data_man.get_snapshot_epoch_count(),
+  );
+  let state = data_man
+    .storage_manager
+    .get_state_for_next_epoch(state_index)
+    .map_err(|e| failed(&e))?
+    .ok_or_else(|| {
+      failed(&format!(
+        "the state of epoch {} is not available",
+        base_epoch
+      ))
+    })?;
+  let mut state_db = StateDb::new(state);
+
+  f(&mut state_db).map_err(|e| failed(&e))?;
+  let state_root = state_db.commit(block_hash, None).map_err(|e| failed(&e))?;
+
+  // The executor skips epochs with a commitment and the receipts of all their
+  // blocks.
+  let block_number = consensus
+    .get_block_number(&block_hash)
+    .map_err(|e| failed(&e))?
+    .ok_or_else(|| failed(&format!("block {:#x} has no number", block_hash)))?;
+  let receipts = vec![Arc::new(BlockReceipts {
+    receipts: vec![],
+    block_number,
+    secondary_reward: U256::zero(),
+    tx_execution_error_messages: vec![],
+  })];
+  data_man.insert_block_execution_result(block_hash, block_hash, receipts[0].clone(), true);
+  data_man.insert_epoch_execution_commitment(
+    block_hash,
+    state_root,
+    BlockHeaderBuilder::compute_block_receipts_root(&receipts),
+    BlockHeaderBuilder::compute_block_logs_bloom_hash(&receipts),
+  );
+
+  producer.mine(0)?;
+  Ok(())
+}
+
+fn account_key(address: &AddressWithSpace) -> primitives::StorageKeyWithSpace<'_> {
+  StorageKey::new_account_key(&address.address).with_space(address.space)
+}
+
+pub fn keccak(data: &[u8]) -> H256 {
+  let mut hasher = Keccak::v256();
+  let mut output = [0u8; 32];
+  hasher.update(data);
+  hasher.finalize(&mut output);
+  H256(output)
+}
+
+pub fn parse_bytes(data: &str) -> Result<Vec<u8>> {
+  data
+    .trim_start_matches("0x")
+    .from_hex()
+    .map_err(|_| NodeError::Runtime(format!("{} is not valid hex data", data)))
+}
+
+/// Parse a 32 byte hex word, shorter values are left padded.
+pub fn parse_word(word: &str) -> Result<H256> {
+  let digits = word.trim_start_matches("0x");
+  if digits.len() > 64 {
+    return Err(NodeError::Runtime(format!(
+      "{} is longer than 32 bytes",
+      word
+    )));
+  }
+  H256::from_str(&format!("{:0>64}", digits))
+    .map_err(|_| NodeError::Runtime(format!("{} is not a valid 32 byte word", word)))
+}
@@ -0,0 +1,163 @@
+use crate::{
+  error::{NodeError, Result},
+  query::to_u64,
+  ConfluxNode,
+};
+use cfxcore::ConsensusGraph;
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Simulated time of a dev node.
+///
+/// Block timestamps are the execution time of both spaces, so every block the
+/// node mines itself takes its timestamp from here. Until the time is changed
+/// the clock stays out of the way and blocks use the wall clock as usual.
+#[derive(Default)]
+pub struct Clock {
+  state: Mutex<ClockState>,
+}
+
+#[derive(Clone, Default)]
+pub struct ClockState {
+  /// Seconds the simulated time is ahead of the wall clock.
+  offset: i64,
+  /// Timestamp of the next block, used once.
+  next: Option<u64>,
+  /// Seconds between consecutive blocks in fixed-step mode.
+  step: Option<u64>,
+  /// Timestamp of the latest block produced with the clock.
+  last: Option<u64>,
+}
+
+impl Clock {
+  /// The timestamp of the next block, `None` while the time was never changed.
+  pub fn next_timestamp(&self) -> Option<u64> {
+    let mut state = self.state.lock();
+    let now = unix_now();
+
+    let timestamp = match (state.next.take(), state.step, state.last) {
+      // Later blocks continue from the set timestamp.
+      (Some(timestamp), _, _) => {
+        state.offset = timestamp as i64 - now as i64;
+        timestamp
+      }
+      (None, Some(step), Some(last)) => last + step,
+      (None, None, None) if state.offset == 0 => return None,
+      (None, _, last) => shifted(now, state.offset).max(last.unwrap_or_default()),
+    };
+    state.last = Some(timestamp);
+    Some(timestamp)
+  }
+
+  pub fn save(&self) -> ClockState {
+    self.state.lock().clone()
+  }
+
+  /// Go back to a saved time. The fixed step is a setting rather than part of
+  /// the time, so it is kept.
+  pub fn restore(&self, saved: ClockState) {
+    let mut state = self.state.lock();
+    *state = ClockState {
+      step: state.step,
+      ..saved
+    };
+  }
+
+  /// Use `timestamp` for the next block. It can't go back before the best
+  /// block or the latest block produced with the clock.
+  pub fn set_next(&self, consensus: &ConsensusGraph, timestamp: u64) -> Result<()> {
+    let best_timestamp = best_timestamp(consensus);
+    if timestamp < best_timestamp {
+      return Err(NodeError::Runtime(format!(
+        "Timestamp {} is before the best block timestamp {}",
+        timestamp, best_timestamp
+      )));
+    }
+
+    let mut state = self.state.lock();
+    if let Some(last) = state.last.filter(|last| timestamp < *last) {
+      return Err(NodeError::Runtime(format!(
+        "Timestamp {} is before the latest block timestamp {}",
+        timestamp, last
+      )));
+    }
+    state.next = Some(timestamp);
+    Ok(())
+  }
+
+  /// Returns how many seconds the clock is ahead of the wall clock.
+  pub fn increase(&self, seconds: u64) -> i64 {
+    let mut state = self.state.lock();
+    state.offset += seconds as i64;
+    if let Some(next) = state.next.as_mut() {
+      *next += seconds;
+    }
+    // In fixed-step mode time only moves with the blocks.
+    if let (Some(_), Some(last)) = (state.step, state.last.as_mut()) {
+      *last += seconds;
+    }
+    state.offset
+  }
+
+  pub fn set_step(&self, step: Option<u64>) {
+    self.state.lock().step = step;
+  }
+}
+
+/// Time controls for contracts that depend on `block.timestamp`.
+///
+/// They apply to the blocks the node mines itself: automine, interval mining,
+/// the mining methods and the dev methods on the eSpace ports. Blocks generated
+/// by the test methods of the client's own JSON-RPC ports, e.g.
+/// `test_generateEmptyBlocks`, keep the wall clock. `mine_custom_block` with an
+/// explicit `timestamp` is not affected.
+#[napi]
+impl ConfluxNode {
+  /// Use `timestamp` (unix seconds) for the next block. Later blocks continue
+  /// from there.
+  #[napi]
+  pub async fn set_next_block_timestamp(&self, timestamp: BigInt) -> Result<()> {
+    let timestamp = to_u64(&timestamp)?;
+    let consensus = self.components().await?.consensus()?;
+    self.clock().await?.set_next(&consensus, timestamp)
+  }
+
+  /// Move the time forward by `seconds`. Resolves with how many seconds the
+  /// node time is ahead of the wall clock in total.
+  #[napi]
+  pub async fn increase_time(&self, seconds: BigInt) -> Result<BigInt> {
+    let seconds = to_u64(&seconds)?;
+    Ok(BigInt::from(self.clock().await?.increase(seconds)))
+  }
+
+  /// Advance the timestamp by exactly `seconds` on every block, independent
+  /// of the wall clock. `0` goes back to following the wall clock.
+  #[napi]
+  pub async fn set_block_timestamp_interval(&self, seconds: u32) -> Result<()> {
+    let step = Some(seconds as u64).filter(|seconds| *seconds > 0);
+    self.clock().await?.set_step(step);
+    Ok(())
+  }
+}
+
+fn shifted(now: u64, offset: i64) -> u64 {
+  (now as i64 + offset).max(0) as u64
+}
+
+fn unix_now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+/// Timestamp of the best block.
+pub fn best_timestamp(consensus: &ConsensusGraph) -> u64 {
+  consensus
+    .data_manager()
+    .block_header_by_hash(&consensus.best_block_hash())
+    .map(|header| header.timestamp())
+    .unwrap_or_default()
+}
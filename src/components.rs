@@ -0,0 +1,113 @@
+use crate::error::{NodeError, Result};
+use blockgen::BlockGenerator;
+use cfx_types::H256;
+use cfxcore::{ConsensusGraph, Notifications, SynchronizationService, TransactionPool};
+use client::{
+  archive::ArchiveClientExtraComponents, common::ClientComponents, full::FullClientExtraComponents,
+  light::LightClientExtraComponents,
+};
+use parking_lot::RwLock;
+use std::{
+  collections::HashSet,
+  sync::{Arc, Weak},
+};
+
+/// Handles into the services of a running client.
+///
+/// Only weak references are kept, the client has to be the sole owner of its
+/// services or `shutdown` can't close the databases.
+#[derive(Clone)]
+pub struct NodeComponents {
+  consensus: Weak<ConsensusGraph>,
+  sync: Option<Weak<SynchronizationService>>,
+  txpool: Option<Weak<TransactionPool>>,
+  blockgen: Option<Weak<BlockGenerator>>,
+  notifications: Option<Weak<Notifications>>,
+  /// Tips of the branches `revert` left behind. They stay in the tree-graph,
+  /// blocks mined later must not reference them or the abandoned blocks and
+  /// their transactions end up in an epoch again.
+  abandoned: Arc<RwLock<HashSet<H256>>>,
+}
+
+impl NodeComponents {
+  pub fn from_archive(
+    client: &ClientComponents<BlockGenerator, ArchiveClientExtraComponents>,
+  ) -> Self {
+    let other = &client.other_components;
+    NodeComponents {
+      consensus: Arc::downgrade(&other.consensus),
+      sync: Some(Arc::downgrade(&other.sync)),
+      txpool: Some(Arc::downgrade(&other.txpool)),
+      blockgen: client.blockgen.as_ref().map(Arc::downgrade),
+      notifications: Some(Arc::downgrade(&other.notifications)),
+      abandoned: Default::default(),
+    }
+  }
+
+  pub fn from_full(client: &ClientComponents<BlockGenerator, FullClientExtraComponents>) -> Self {
+    let other = &client.other_components;
+    NodeComponents {
+      consensus: Arc::downgrade(&other.consensus),
+      sync: Some(Arc::downgrade(&other.sync)),
+      txpool: Some(Arc::downgrade(&other.txpool)),
+      blockgen: client.blockgen.as_ref().map(Arc::downgrade),
+      notifications: Some(Arc::downgrade(&other.notifications)),
+      abandoned: Default::default(),
+    }
+  }
+
+  pub fn from_light(client: &ClientComponents<(), LightClientExtraComponents>) -> Self {
+    NodeComponents {
+      consensus: Arc::downgrade(&client.other_components.consensus),
+      sync: None,
+      txpool: None,
+      blockgen: None,
+      notifications: None,
+      abandoned: Default::default(),
+    }
+  }
+
+  pub fn consensus(&self) -> Result<Arc<ConsensusGraph>> {
+    upgrade(&self.consensus, "consensus")
+  }
+
+  pub fn sync(&self) -> Result<Arc<SynchronizationService>> {
+    upgrade_optional(&self.sync, "sync service")
+  }
+
+  pub fn txpool(&self) -> Result<Arc<TransactionPool>> {
+    upgrade_optional(&self.txpool, "transaction pool")
+  }
+
+  pub fn blockgen(&self) -> Result<Arc<BlockGenerator>> {
+    upgrade_optional(&self.blockgen, "block generator")
+  }
+
+  pub fn notifications(&self) -> Result<Arc<Notifications>> {
+    upgrade_optional(&self.notifications, "consensus notifications")
+  }
+
+  pub fn abandon(&self, tips: impl IntoIterator<Item = H256>) {
+    self.abandoned.write().extend(tips);
+  }
+
+  pub fn is_abandoned(&self, hash: &H256) -> bool {
+    self.abandoned.read().contains(hash)
+  }
+}
+
+fn upgrade<T>(weak: &Weak<T>, name: &str) -> Result<Arc<T>> {
+  weak
+    .upgrade()
+    .ok_or_else(|| NodeError::Runtime(format!("The node {} has been shut down", name)))
+}
+
+fn upgrade_optional<T>(weak: &Option<Weak<T>>, name: &str) -> Result<Arc<T>> {
+  match weak {
+    Some(weak) => upgrade(weak, name),
+    None => Err(NodeError::Runtime(format!(
+      "The {} is not available for this node type",
+      name
+    ))),
+  }
+}
@@ -1,19 +1,19 @@
-use crate::error::NodeError;
+use crate::{anvil::ANVIL_API, error::NodeError, globals::claim_cip112_transition_height};
 use cfx_config::Configuration;
 use cfx_rpc_builder::RpcModuleSelection;
 use cfx_rpc_cfx_types::apis::ApiSet;
 use cfxcore::NodeType;
 use client::configuration::RawConfiguration;
 use napi_derive::napi;
-use primitives::block_header::CIP112_TRANSITION_HEIGHT;
 use std::{
-  fs::File,
+  fs::{self, File},
   io::{BufWriter, Write},
-  path::Path,
+  path::{Component, Path, PathBuf},
   str::FromStr,
+  time::Duration,
 };
 #[napi(object)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConfluxConfig {
   pub config_file: Option<String>,
 
@@ -90,6 +90,22 @@ pub struct ConfluxConfig {
   ///  The private key of the genesis (eSpace), every account will be receive 10000 CFX
   pub genesis_evm_secrets: Option<Vec<String>>,
 
+  // ============= Startup Configuration =============
+  /// How long `start_node` may take as a whole. When the deadline passes the
+  /// half-started node is torn down and a timeout error is returned.
+  /// @default 120000
+  pub startup_timeout_ms: Option<u32>,
+
+  /// How long `start_node` waits for the node to become ready: genesis executed,
+  /// consensus in `NormalSyncPhase` and every configured RPC port bound.
+  /// @default 20000
+  pub ready_timeout_ms: Option<u32>,
+
+  /// When set, the node shuts itself down if `heartbeat` was not called for this
+  /// many milliseconds, so it doesn't outlive a host that was killed.
+  /// The watchdog is armed once the node is ready.
+  pub watchdog_timeout_ms: Option<u32>,
+
   // ============= Network Configuration =============
   /// `tcp_port` is the TCP port that the process listens for P2P messages. The default is 32323.
   /// @default 32323
@@ -109,6 +125,9 @@ pub struct ConfluxConfig {
   pub public_rpc_apis: Option<String>,
 
   /// Possible eSpace names are: eth, ethpubsub, ethdebug.
+  /// Dev mode nodes also accept `anvil`, which serves Hardhat and Anvil
+  /// compatible `evm_*`, `anvil_*` and `hardhat_*` methods on the eSpace ports.
+  /// The ports then only listen on 127.0.0.1.
   ///  @default 'evm'
   pub public_evm_rpc_apis: Option<String>,
 
@@ -215,31 +234,104 @@ pub struct ConfluxConfig {
   pub get_logs_filter_max_limit: Option<u32>,
 }
 
-impl ConfluxConfig {
-  pub fn to_configuration(&self, temp_dir_path: &Path) -> Result<Configuration, NodeError> {
-    if let Some(config_file) = &self.config_file {
-      let conf = Configuration {
-        raw_conf: RawConfiguration::from_file(config_file)
-          .map_err(|e| NodeError::Configuration(e))?,
-      };
-
-      if CIP112_TRANSITION_HEIGHT.get().is_none() {
-        CIP112_TRANSITION_HEIGHT
-          .set(conf.raw_conf.cip112_transition_height.unwrap_or(u64::MAX))
-          .map_err(|_| NodeError::Configuration("CIP112_TRANSITION_HEIGHT already set".into()))?;
-      }
-      return Ok(conf);
+// Every field has to be listed, the struct literal fails to compile otherwise.
+macro_rules! merge_config {
+  ($base:expr, $overrides:expr, { $($field:ident),* $(,)? }) => {
+    ConfluxConfig {
+      $($field: $overrides.$field.or($base.$field)),*
     }
+  };
+}
 
-    let mut conf = Configuration::default();
+impl ConfluxConfig {
+  /// Returns a copy of this config where every field set in `overrides` wins.
+  pub fn merge(self, overrides: ConfluxConfig) -> ConfluxConfig {
+    merge_config!(self, overrides, {
+      config_file,
+      node_type,
+      block_db_type,
+      conflux_data_dir,
+      block_db_dir,
+      netconf_dir,
+      chain_id,
+      evm_chain_id,
+      bootnodes,
+      mining_author,
+      stratum_listen_address,
+      mining_type,
+      stratum_port,
+      stratum_secret,
+      pow_problem_window_size,
+      dev_block_interval_ms,
+      dev_pack_tx_immediately,
+      genesis_secrets,
+      genesis_evm_secrets,
+      startup_timeout_ms,
+      ready_timeout_ms,
+      watchdog_timeout_ms,
+      tcp_port,
+      udp_port,
+      public_address,
+      public_rpc_apis,
+      public_evm_rpc_apis,
+      jsonrpc_ws_port,
+      jsonrpc_http_port,
+      jsonrpc_tcp_port,
+      jsonrpc_http_eth_port,
+      jsonrpc_ws_eth_port,
+      jsonrpc_local_tcp_port,
+      jsonrpc_local_http_port,
+      jsonrpc_local_ws_port,
+      jsonrpc_http_keep_alive,
+      dev_pos_private_key_encryption_password,
+      pos_reference_enable_height,
+      pos_config_path,
+      pos_initial_nodes_path,
+      pos_private_key_path,
+      default_transition_time,
+      cip1559_transition_height,
+      hydra_transition_number,
+      hydra_transition_height,
+      cip112_transition_height,
+      log_conf,
+      log_level,
+      print_memory_usage_period_s,
+      poll_lifetime_in_seconds,
+      get_logs_filter_max_limit,
+    })
+  }
+
+  pub fn startup_timeout(&self) -> Duration {
+    Duration::from_millis(self.startup_timeout_ms.unwrap_or(120_000) as u64)
+  }
 
-    self.apply_to_raw_config(&mut conf.raw_conf, temp_dir_path)?;
+  pub fn ready_timeout(&self) -> Duration {
+    Duration::from_millis(self.ready_timeout_ms.unwrap_or(20_000) as u64)
+  }
 
-    if CIP112_TRANSITION_HEIGHT.get().is_none() {
-      CIP112_TRANSITION_HEIGHT
-        .set(conf.raw_conf.cip112_transition_height.unwrap_or(u64::MAX))
-        .map_err(|_| NodeError::Configuration("CIP112_TRANSITION_HEIGHT already set".into()))?;
-    }
+  pub fn watchdog_timeout(&self) -> Option<Duration> {
+    self
+      .watchdog_timeout_ms
+      .map(|ms| Duration::from_millis(ms as u64))
+  }
+
+  pub fn to_configuration(&self, data_dir: &Path) -> Result<Configuration, NodeError> {
+    let mut conf = match &self.config_file {
+      Some(config_file) => Configuration {
+        raw_conf: RawConfiguration::from_file(&resolve_path(data_dir, config_file))
+          .map_err(NodeError::Configuration)?,
+      },
+      None => {
+        let mut conf = Configuration::default();
+        self.apply_to_raw_config(&mut conf.raw_conf, data_dir)?;
+        conf
+      }
+    };
+
+    resolve_relative_paths(&mut conf.raw_conf, data_dir);
+    resolve_pos_config_paths(&mut conf.raw_conf, data_dir)?;
+
+    claim_cip112_transition_height(conf.raw_conf.cip112_transition_height.unwrap_or(u64::MAX))?;
 
     Ok(conf)
   }
@@ -281,12 +373,8 @@ impl ConfluxConfig {
     raw_conf: &mut RawConfiguration,
     temp_dir_path: &Path,
   ) -> Result<(), NodeError> {
-    // Directory configuration
-
-    let data_dir = self
-      .conflux_data_dir
-      .clone()
-      .unwrap_or_else(|| temp_dir_path.to_string_lossy().to_string());
+    // Directory configuration, `temp_dir_path` is already resolved from `conflux_data_dir`
+    let data_dir = temp_dir_path.to_string_lossy().to_string();
 
     raw_conf.conflux_data_dir = data_dir.clone();
     raw_conf.block_db_dir = Some(format!("{}/blockchain_db", &data_dir));
@@ -346,6 +434,32 @@ impl ConfluxConfig {
     raw_conf.public_address = self.public_address.clone();
   }
 
+  /// `console.log` calls are picked up from the execution traces, which are
+  /// only recorded when a callback wants them. A config file has to turn them
+  /// on itself.
+  pub fn trace_console_logs(&self, raw_conf: &mut RawConfiguration) -> Result<(), NodeError> {
+    match &self.config_file {
+      None => raw_conf.executive_trace = true,
+      Some(config_file) if !raw_conf.executive_trace => {
+        return Err(NodeError::Configuration(format!(
+          "onConsoleLog needs executive_trace = true in {}",
+          config_file
+        )))
+      }
+      Some(_) => {}
+    }
+    Ok(())
+  }
+
+  /// Whether `public_evm_rpc_apis` selects the Hardhat and Anvil compatible
+  /// methods.
+  pub fn anvil_rpc_enabled(&self) -> bool {
+    self
+      .public_evm_rpc_apis
+      .as_deref()
+      .is_some_and(|apis| split_evm_apis(apis).1)
+  }
+
   fn apply_rpc_config(&self, raw_conf: &mut RawConfiguration) {
     // JSON-RPC Configuration
     let default_rpc_apis = ApiSet::from_str("all").unwrap();
@@ -357,12 +471,17 @@ impl ConfluxConfig {
       });
 
     let default_evm_apis = RpcModuleSelection::from_str("evm,ethdebug").unwrap();
-    raw_conf.public_evm_rpc_apis = self
-      .public_evm_rpc_apis
-      .as_ref()
-      .map_or(default_evm_apis.clone(), |s| {
-        RpcModuleSelection::from_str(s).unwrap_or(default_evm_apis)
-      });
+    raw_conf.public_evm_rpc_apis =
+      self
+        .public_evm_rpc_apis
+        .as_ref()
+        .map_or(default_evm_apis.clone(), |s| {
+          let (apis, _) = split_evm_apis(s);
+          if apis.is_empty() {
+            return default_evm_apis;
+          }
+          RpcModuleSelection::from_str(&apis).unwrap_or(default_evm_apis)
+        });
 
     raw_conf.jsonrpc_ws_port = self.jsonrpc_ws_port;
     raw_conf.jsonrpc_http_port = self.jsonrpc_http_port;
@@ -442,3 +561,107 @@ impl ConfluxConfig {
     Ok(file_path.to_string_lossy().to_string())
   }
 }
+
+/// Paths in the configuration used to be resolved against the process working
+/// directory, which was switched to the data directory. Resolve them against the
+/// data directory explicitly so several nodes can share one process.
+fn resolve_relative_paths(raw_conf: &mut RawConfiguration, data_dir: &Path) {
+  raw_conf.conflux_data_dir = resolve_path(data_dir, &raw_conf.conflux_data_dir);
+  raw_conf.pos_initial_nodes_path = resolve_path(data_dir, &raw_conf.pos_initial_nodes_path);
+  raw_conf.pos_private_key_path = resolve_path(data_dir, &raw_conf.pos_private_key_path);
+
+  for path in [
+    &mut raw_conf.block_db_dir,
+    &mut raw_conf.netconf_dir,
+    &mut raw_conf.pos_config_path,
+    &mut raw_conf.genesis_secrets,
+    &mut raw_conf.genesis_evm_secrets,
+    &mut raw_conf.log_conf,
+  ]
+  .into_iter()
+  .flatten()
+  {
+    *path = resolve_path(data_dir, path);
+  }
+}
+
+/// The PoS config names its genesis file relative to itself and its log file
+/// relative to the working directory, which every node shares. Each node gets
+/// a copy in its data directory with both paths resolved.
+fn resolve_pos_config_paths(
+  raw_conf: &mut RawConfiguration,
+  data_dir: &Path,
+) -> Result<(), NodeError> {
+  let Some(pos_config) = raw_conf.pos_config_path.clone() else {
+    return Ok(());
+  };
+  let pos_config = Path::new(&pos_config);
+  // Conflux reports a missing PoS config itself.
+  let Some(pos_dir) = pos_config.parent().filter(|_| pos_config.is_file()) else {
+    return Ok(());
+  };
+
+  let resolved = write_resolved_yaml(pos_config, data_dir, "pos_config.yaml", |doc| {
+    resolve_yaml_path(doc, &["execution", "genesis_file_location"], pos_dir);
+    resolve_yaml_path(doc, &["logger", "file"], data_dir);
+  })?;
+  raw_conf.pos_config_path = Some(resolved.to_string_lossy().to_string());
+  Ok(())
+}
+
+/// Write a copy of the YAML file at `path` to `name` in `data_dir`, after
+/// `resolve` rewrote its relative paths.
+pub fn write_resolved_yaml(
+  path: &Path,
+  data_dir: &Path,
+  name: &str,
+  resolve: impl FnOnce(&mut serde_yaml::Value),
+) -> Result<PathBuf, NodeError> {
+  let invalid = |e: String| NodeError::Configuration(format!("Failed to read {:?}: {}", path, e));
+
+  let content = fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+  let mut doc: serde_yaml::Value =
+    serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+  resolve(&mut doc);
+
+  let resolved = data_dir.join(name);
+  let content = serde_yaml::to_string(&doc).map_err(|e| invalid(e.to_string()))?;
+  fs::write(&resolved, content)
+    .map_err(|e| NodeError::Initialization(format!("Failed to write {:?}: {}", resolved, e)))?;
+  Ok(resolved)
+}
+
+/// Resolve the string at `keys` against `base`, if the document has one.
+pub fn resolve_yaml_path(doc: &mut serde_yaml::Value, keys: &[&str], base: &Path) {
+  let mut value = Some(doc);
+  for key in keys {
+    value = value.and_then(|value| value.get_mut(*key));
+  }
+  if let Some(serde_yaml::Value::String(path)) = value {
+    *path = resolve_path(base, path);
+  }
+}
+
+pub fn resolve_path(base: &Path, path: &str) -> String {
+  let path = PathBuf::from(path);
+  if path.is_absolute() {
+    path.to_string_lossy().to_string()
+  } else {
+    let path: PathBuf = path
+      .components()
+      .filter(|component| *component != Component::CurDir)
+      .collect();
+    base.join(path).to_string_lossy().to_string()
+  }
+}
+
+/// Split the `anvil` name off an eSpace API list, the rest is handed to
+/// `RpcModuleSelection`. Returns whether it was there.
+fn split_evm_apis(apis: &str) -> (String, bool) {
+  let (anvil, rest): (Vec<&str>, Vec<&str>) = apis
+    .split(',')
+    .map(str::trim)
+    .filter(|api| !api.is_empty())
+    .partition(|api| api.eq_ignore_ascii_case(ANVIL_API));
+  (rest.join(","), !anvil.is_empty())
+}
@@ -0,0 +1,313 @@
+use crate::{
+  background::BackgroundThread, callback::CallbackSlot, cheats::keccak, components::NodeComponents,
+  query::hex,
+};
+use cfx_parity_trace_types::Action;
+use cfx_types::{Address, Space, H256, U256};
+use cfxcore::ConsensusGraph;
+use log::{info, warn};
+use napi_derive::napi;
+use primitives::EpochNumber;
+use rustc_hex::ToHex;
+use std::{
+  collections::HashMap,
+  str::FromStr,
+  sync::{Arc, OnceLock},
+  time::Duration,
+};
+
+/// The address `console.sol` sends its calls to.
+const CONSOLE_ADDRESS: &str = "000000000000000000636f6e736f6c652e6c6f67";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[napi(object)]
+pub struct ConsoleLog {
+  pub transaction_hash: String,
+  /// How many calls deep the `console.log` call was made, 1 for calls from
+  /// the contract the transaction calls.
+  pub depth: u32,
+  /// The arguments, formatted the way Hardhat prints them.
+  pub message: String,
+}
+
+/// Picks up `console.log` calls of eSpace contracts from the execution traces
+/// of every newly executed epoch.
+pub struct ConsoleWatcher {
+  thread: BackgroundThread,
+}
+
+impl ConsoleWatcher {
+  /// Start watching on a background thread, beginning after the latest
+  /// executed epoch. Light nodes execute nothing, so there is nothing to watch.
+  pub fn start(
+    components: NodeComponents,
+    listener: Arc<CallbackSlot<ConsoleLog>>,
+  ) -> Option<Arc<Self>> {
+    let consensus = components.consensus().ok()?;
+    components.txpool().ok()?;
+
+    let watcher = Arc::new(ConsoleWatcher {
+      thread: BackgroundThread::default(),
+    });
+
+    let runner = watcher.clone();
+    watcher
+      .thread
+      .spawn("xcfx-console", move || runner.run(&consensus, &listener));
+    Some(watcher)
+  }
+
+  pub fn stop(&self) {
+    self.thread.stop();
+  }
+
+  fn run(&self, consensus: &ConsensusGraph, listener: &CallbackSlot<ConsoleLog>) {
+    let mut last_epoch = consensus.best_executed_state_epoch_number();
+
+    while self.thread.wait(Some(POLL_INTERVAL)) {
+      // Reverts and reorgs execute lower epochs again.
+      let executed = consensus.best_executed_state_epoch_number();
+      last_epoch = last_epoch.min(executed);
+      for epoch in last_epoch + 1..=executed {
+        for log in epoch_console_logs(consensus, epoch) {
+          info!(
+            "console.log in {} at depth {}: {}",
+            log.transaction_hash, log.depth, log.message
+          );
+          listener.notify(log);
+        }
+      }
+      last_epoch = executed;
+    }
+  }
+}
+
+/// The `console.log` calls of the transactions executed in `epoch`, in
+/// execution order.
+fn epoch_console_logs(consensus: &ConsensusGraph, epoch: u64) -> Vec<ConsoleLog> {
+  let data_man = consensus.data_manager();
+  let Ok(block_hashes) = consensus.get_block_hashes_by_epoch(EpochNumber::Number(epoch)) else {
+    return Vec::new();
+  };
+  let Some(pivot_hash) = block_hashes.last().copied() else {
+    return Vec::new();
+  };
+
+  let mut logs = Vec::new();
+  for block_hash in &block_hashes {
+    let (Some(block), Some(traces)) = (
+      data_man.block_by_hash(block_hash, false),
+      data_man.block_traces_by_hash_with_epoch(block_hash, &pivot_hash, false, false),
+    ) else {
+      continue;
+    };
+
+    for (tx, tx_traces) in block.transactions.iter().zip(traces.0) {
+      if tx.space() != Space::Ethereum {
+        continue;
+      }
+      // Depth is the number of call frames open when a call is made.
+      let mut depth = 0u32;
+      for trace in tx_traces.0 {
+        match trace.action {
+          Action::Call(call) => {
+            if call.space == Space::Ethereum && call.to == console_address() {
+              match format_call(&call.input) {
+                Some(message) => logs.push(ConsoleLog {
+                  transaction_hash: hex(&tx.hash()),
+                  depth,
+                  message,
+                }),
+                None => warn!(
+                  "Unknown console.log call in {:#x}: 0x{}",
+                  tx.hash(),
+                  call.input.to_hex::<String>()
+                ),
+              }
+            }
+            depth += 1;
+          }
+          Action::Create(_) => depth += 1,
+          Action::CallResult(_) | Action::CreateResult(_) => depth = depth.saturating_sub(1),
+          _ => {}
+        }
+      }
+    }
+  }
+  logs
+}
+
+fn console_address() -> Address {
+  Address::from_str(CONSOLE_ADDRESS).expect("valid console address")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Param {
+  Uint,
+  Int,
+  String,
+  Bool,
+  Address,
+  Bytes,
+  FixedBytes(usize),
+}
+
+/// The parameters of every function in `console.sol` by selector.
+fn signatures() -> &'static HashMap<[u8; 4], Vec<Param>> {
+  static SIGNATURES: OnceLock<HashMap<[u8; 4], Vec<Param>>> = OnceLock::new();
+  SIGNATURES.get_or_init(|| {
+    let single = |name: &str, ty: &str, param| (name.to_string(), vec![(ty.to_string(), param)]);
+    let mut functions = vec![
+      ("log".to_string(), vec![]),
+      single("logInt", "int256", Param::Int),
+      single("logInt", "int", Param::Int),
+      single("logUint", "uint256", Param::Uint),
+      single("logUint", "uint", Param::Uint),
+      single("logString", "string", Param::String),
+      single("logBool", "bool", Param::Bool),
+      single("logAddress", "address", Param::Address),
+      single("logBytes", "bytes", Param::Bytes),
+      single("log", "int256", Param::Int),
+      single("log", "int", Param::Int),
+    ];
+    for n in 1..=32 {
+      functions.push(single(
+        &format!("logBytes{}", n),
+        &format!("bytes{}", n),
+        Param::FixedBytes(n),
+      ));
+    }
+
+    // `log` takes up to four of these in any combination. Old versions of
+    // `console.sol` spelled `uint256` as `uint`, which changes the selector.
+    for uint in ["uint256", "uint"] {
+      let types = [
+        (uint, Param::Uint),
+        ("string", Param::String),
+        ("bool", Param::Bool),
+        ("address", Param::Address),
+      ];
+      let mut combinations = vec![vec![]];
+      for _ in 0..4 {
+        combinations = combinations
+          .iter()
+          .flat_map(|params: &Vec<(String, Param)>| {
+            types.iter().map(move |(ty, param)| {
+              let mut params = params.clone();
+              params.push((ty.to_string(), *param));
+              params
+            })
+          })
+          .collect();
+        functions.extend(
+          combinations
+            .iter()
+            .map(|params| ("log".to_string(), params.clone())),
+        );
+      }
+    }
+
+    functions
+      .into_iter()
+      .map(|(name, params)| {
+        let types: Vec<&str> = params.iter().map(|(ty, _)| ty.as_str()).collect();
+        let hash = keccak(format!("{}({})", name, types.join(",")).as_bytes());
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&hash[..4]);
+        (
+          selector,
+          params.into_iter().map(|(_, param)| param).collect(),
+        )
+      })
+      .collect()
+  })
+}
+
+/// Decode the call data of a `console.sol` call and format it like Hardhat,
+/// with printf style substitution when the first argument is a string.
+fn format_call(input: &[u8]) -> Option<String> {
+  let params = signatures().get(input.get(..4)?)?;
+  let data = &input[4..];
+  let values = params
+    .iter()
+    .enumerate()
+    .map(|(i, param)| decode(data, i, *param))
+    .collect::<Option<Vec<_>>>()?;
+
+  let Some((first, rest)) = values.split_first() else {
+    return Some(String::new());
+  };
+  if params[0] != Param::String {
+    return Some(values.join(" "));
+  }
+
+  let mut rest = rest.iter();
+  let mut message = String::new();
+  let mut chars = first.chars().peekable();
+  while let Some(c) = chars.next() {
+    match (c, chars.peek()) {
+      ('%', Some('%')) => {
+        chars.next();
+        message.push('%');
+      }
+      ('%', Some('s' | 'd' | 'i' | 'o')) if !rest.as_slice().is_empty() => {
+        chars.next();
+        message.push_str(rest.next()?);
+      }
+      _ => message.push(c),
+    }
+  }
+  for value in rest {
+    message.push(' ');
+    message.push_str(value);
+  }
+  Some(message)
+}
+
+/// Decode ABI argument `index` of `data`.
+fn decode(data: &[u8], index: usize, param: Param) -> Option<String> {
+  let word = |offset: usize| data.get(offset..offset.checked_add(32)?);
+  let head = word(index * 32)?;
+
+  Some(match param {
+    Param::Uint => U256::from_big_endian(head).to_string(),
+    Param::Int => {
+      let value = U256::from_big_endian(head);
+      match value.bit(255) {
+        true => format!("-{}", (!value).overflowing_add(U256::one()).0),
+        false => value.to_string(),
+      }
+    }
+    Param::Bool => (!U256::from_big_endian(head).is_zero()).to_string(),
+    Param::Address => checksum(&Address::from_slice(&head[12..])),
+    Param::FixedBytes(n) => format!("0x{}", head[..n].to_hex::<String>()),
+    Param::String | Param::Bytes => {
+      let offset = usize::try_from(U256::from_big_endian(head)).ok()?;
+      let len = usize::try_from(U256::from_big_endian(word(offset)?)).ok()?;
+      let start = offset.checked_add(32)?;
+      let bytes = data.get(start..start.checked_add(len)?)?;
+      match param {
+        Param::String => String::from_utf8_lossy(bytes).into_owned(),
+        _ => format!("0x{}", bytes.to_hex::<String>()),
+      }
+    }
+  })
+}
+
+/// EIP-55 mixed case hex.
+fn checksum(address: &Address) -> String {
+  let lower: String = address.as_bytes().to_hex();
+  let hash: H256 = keccak(lower.as_bytes());
+  let checksummed: String = lower
+    .chars()
+    .enumerate()
+    .map(|(i, c)| {
+      let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0xf;
+      match nibble >= 8 {
+        true => c.to_ascii_uppercase(),
+        false => c,
+      }
+    })
+    .collect();
+  format!("0x{}", checksummed)
+}
@@ -0,0 +1,151 @@
+use crate::{
+  callback::{CallbackSlot, JsCallback},
+  status::StatusTracker,
+  ExitSign,
+};
+use log::error;
+use napi_derive::napi;
+use parking_lot::{const_mutex, Mutex};
+use std::{
+  backtrace::Backtrace,
+  panic::{self, PanicHookInfo},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Once, Weak,
+  },
+  thread,
+};
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+// Panics on conflux's own threads can't be attributed to a node, so they are
+// reported to every monitor that is still alive.
+static MONITORS: Mutex<Vec<Weak<CrashMonitor>>> = const_mutex(Vec::new());
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashKind {
+  /// A thread panicked.
+  Panic,
+  /// The client signalled its exit condvar without `stop_node` being called.
+  Exit,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+  pub kind: CrashKind,
+  pub thread_name: String,
+  pub message: String,
+  pub backtrace: Option<String>,
+  /// Unix timestamp in milliseconds.
+  pub timestamp: i64,
+}
+
+pub struct CrashMonitor {
+  status: Arc<StatusTracker>,
+  listener: CallbackSlot<CrashReport>,
+}
+
+impl CrashMonitor {
+  pub fn new(status: Arc<StatusTracker>) -> Arc<Self> {
+    INSTALL_PANIC_HOOK.call_once(install_panic_hook);
+
+    let monitor = Arc::new(CrashMonitor {
+      status,
+      listener: CallbackSlot::default(),
+    });
+    MONITORS.lock().push(Arc::downgrade(&monitor));
+    monitor
+  }
+
+  pub fn set_listener(&self, callback: JsCallback<CrashReport>) {
+    self.listener.set(callback);
+  }
+
+  /// Watch the exit condvar of one node run. The watcher ends once the exit
+  /// flag is set, which `stop_node` always does.
+  pub fn watch_exit(self: &Arc<Self>, exit_sign: ExitSign, stopping: Arc<AtomicBool>) {
+    let monitor = Arc::downgrade(self);
+    let spawned = thread::Builder::new()
+      .name("xcfx-exit-watcher".into())
+      .spawn(move || {
+        {
+          let (lock, cvar) = &*exit_sign;
+          let mut exited = lock.lock();
+          while !*exited {
+            cvar.wait(&mut exited);
+          }
+        }
+
+        if stopping.load(Ordering::SeqCst) {
+          return;
+        }
+        if let Some(monitor) = monitor.upgrade() {
+          let report = CrashReport {
+            kind: CrashKind::Exit,
+            thread_name: current_thread_name(),
+            message: "The node exited without stop_node being called".to_string(),
+            backtrace: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+          };
+          monitor.status.fail(&report.message);
+          monitor.report(report);
+        }
+      });
+
+    if let Err(e) = spawned {
+      error!("Failed to spawn the exit watcher: {}", e);
+    }
+  }
+
+  fn report(&self, report: CrashReport) {
+    error!(
+      "Node crash on thread {}: {}",
+      report.thread_name, report.message
+    );
+    self.listener.notify(report);
+  }
+}
+
+fn install_panic_hook() {
+  let previous = panic::take_hook();
+  panic::set_hook(Box::new(move |info| {
+    let report = CrashReport {
+      kind: CrashKind::Panic,
+      thread_name: current_thread_name(),
+      message: panic_message(info),
+      backtrace: Some(Backtrace::force_capture().to_string()),
+      timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let monitors: Vec<Arc<CrashMonitor>> = {
+      let mut monitors = MONITORS.lock();
+      monitors.retain(|monitor| monitor.strong_count() > 0);
+      monitors.iter().filter_map(Weak::upgrade).collect()
+    };
+    for monitor in monitors {
+      monitor.report(report.clone());
+    }
+
+    previous(info);
+  }));
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+  let payload = info
+    .payload()
+    .downcast_ref::<&str>()
+    .map(|s| s.to_string())
+    .or_else(|| info.payload().downcast_ref::<String>().cloned())
+    .unwrap_or_else(|| "Box<dyn Any>".to_string());
+
+  match info.location() {
+    Some(location) => format!("{} at {}", payload, location),
+    None => payload,
+  }
+}
+
+fn current_thread_name() -> String {
+  thread::current().name().unwrap_or("<unnamed>").to_string()
+}
@@ -7,6 +7,9 @@ pub enum NodeError {
   Configuration(String),
   Runtime(String),
   Shutdown(String),
+  Timeout(String),
+  Cancelled(String),
+  Rpc(String),
 }
 
 impl fmt::Display for NodeError {
@@ -16,6 +19,9 @@ impl fmt::Display for NodeError {
       NodeError::Configuration(msg) => write!(f, "Configuration Error: {}", msg),
       NodeError::Runtime(msg) => write!(f, "Runtime Error: {}", msg),
       NodeError::Shutdown(msg) => write!(f, "Shutdown Error: {}", msg),
+      NodeError::Timeout(msg) => write!(f, "Timeout Error: {}", msg),
+      NodeError::Cancelled(msg) => write!(f, "Cancelled Error: {}", msg),
+      NodeError::Rpc(msg) => write!(f, "RPC Error: {}", msg),
     }
   }
 }
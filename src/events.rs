@@ -0,0 +1,351 @@
+use crate::{
+  background::BackgroundThread,
+  callback::{CallbackSlot, JsCallback},
+  components::NodeComponents,
+  logs::LogSubscription,
+  query::hex,
+  transactions::{TransactionListener, TransactionTracker},
+  ConfluxNode,
+};
+use cfx_parameters::consensus::DEFERRED_STATE_EPOCH_COUNT;
+use cfx_types::H256;
+use cfxcore::{ConsensusGraph, TransactionPool};
+use napi::{bindgen_prelude::BigInt, tokio::task};
+use napi_derive::napi;
+use parking_lot::Mutex;
+use primitives::EpochNumber;
+use std::{collections::BTreeMap, mem, sync::Arc, time::Duration};
+
+// Execution and the pool don't notify, they are checked this often while an
+// epoch is due or transactions are followed.
+const CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+#[napi(object)]
+pub struct BlockEvent {
+  pub hash: String,
+  pub height: BigInt,
+  /// `null` if the block was not part of an epoch yet when it was inserted.
+  pub epoch_number: Option<BigInt>,
+  /// Whether the block was the pivot of its epoch when it was inserted.
+  pub pivot: bool,
+  pub timestamp: BigInt,
+}
+
+#[napi(object)]
+pub struct EpochEvent {
+  pub epoch_number: BigInt,
+  /// The pivot block of the epoch.
+  pub hash: String,
+  pub height: BigInt,
+  pub timestamp: BigInt,
+  /// All blocks of the epoch in execution order, the pivot block last.
+  pub block_hashes: Vec<String>,
+}
+
+/// The JS callbacks for chain progress. They outlive a single run of the node,
+/// so they are registered once for every restart.
+#[derive(Default)]
+pub struct ChainListeners {
+  block: CallbackSlot<BlockEvent>,
+  epoch: CallbackSlot<EpochEvent>,
+  pub transaction: TransactionListener,
+}
+
+/// What consensus announced since the watcher last looked.
+enum Notification {
+  /// A block was inserted.
+  Inserted(H256),
+  /// An epoch joined the pivot chain, with its blocks in execution order.
+  Ordered(u64, Vec<H256>),
+}
+
+/// Follows the consensus notifications and reports new blocks, executed
+/// epochs and their logs.
+///
+/// Blocks are reported as they are inserted. Epochs are reported once they are
+/// executed, after a pivot switch the new epochs are reported again.
+pub struct ChainWatcher {
+  thread: BackgroundThread,
+  components: NodeComponents,
+  /// The subscriptions to inserted blocks and ordered epochs.
+  subscriptions: Mutex<Option<(u64, u64)>>,
+  inbox: Mutex<Vec<Notification>>,
+  logs: Mutex<Vec<LogSubscription>>,
+}
+
+impl ChainWatcher {
+  /// Start watching on a background thread, beginning after the current best
+  /// epoch. Light nodes execute nothing, so there is nothing to watch.
+  pub fn start(components: NodeComponents, listeners: Arc<ChainListeners>) -> Option<Arc<Self>> {
+    let consensus = components.consensus().ok()?;
+    let txpool = components.txpool().ok()?;
+    let notifications = components.notifications().ok()?;
+
+    let watcher = Arc::new(ChainWatcher {
+      thread: BackgroundThread::default(),
+      components,
+      subscriptions: Mutex::new(None),
+      inbox: Mutex::new(Vec::new()),
+      logs: Mutex::new(Vec::new()),
+    });
+
+    // Subscribe before the cursor starts, so no epoch falls in between.
+    let mut blocks = notifications.new_block_hashes.subscribe();
+    let mut epochs = notifications.epochs_ordered.subscribe();
+    *watcher.subscriptions.lock() = Some((blocks.id, epochs.id));
+    let receiver = watcher.clone();
+    task::spawn(async move {
+      while let Some(hash) = blocks.recv().await {
+        receiver.receive(Notification::Inserted(hash));
+      }
+    });
+    let receiver = watcher.clone();
+    task::spawn(async move {
+      while let Some((epoch, block_hashes)) = epochs.recv().await {
+        receiver.receive(Notification::Ordered(epoch, block_hashes));
+      }
+    });
+
+    let runner = watcher.clone();
+    watcher.thread.spawn("xcfx-chain-events", move || {
+      runner.run(&consensus, &txpool, &listeners)
+    });
+    Some(watcher)
+  }
+
+  pub fn stop(&self) {
+    // Ends the receiving tasks.
+    if let (Some((blocks, epochs)), Ok(notifications)) = (
+      self.subscriptions.lock().take(),
+      self.components.notifications(),
+    ) {
+      notifications.new_block_hashes.unsubscribe(blocks);
+      notifications.epochs_ordered.unsubscribe(epochs);
+    }
+    self.thread.stop();
+    // Ends the log streams.
+    self.logs.lock().clear();
+  }
+
+  /// The thread is woken up to catch the stream up on history.
+  pub fn subscribe_logs(&self, subscription: LogSubscription) {
+    self.logs.lock().push(subscription);
+    self.thread.wake();
+  }
+
+  fn receive(&self, notification: Notification) {
+    self.inbox.lock().push(notification);
+    self.thread.wake();
+  }
+
+  fn deliver_logs(&self, consensus: &ConsensusGraph, changes: &[ChainChange], executed: u64) {
+    self
+      .logs
+      .lock()
+      .retain_mut(|subscription| subscription.deliver(consensus, changes, executed));
+  }
+
+  fn run(&self, consensus: &ConsensusGraph, txpool: &TransactionPool, listeners: &ChainListeners) {
+    let mut cursor = ChainCursor::new(consensus);
+    // Transactions are only followed while someone listens.
+    let mut transactions: Option<TransactionTracker> = None;
+    let mut last_stats = None;
+
+    loop {
+      let check = cursor.awaits_execution(consensus) || listeners.transaction.is_set();
+      if !self.thread.wait(check.then_some(CHECK_INTERVAL)) {
+        return;
+      }
+
+      let mut changes = Vec::new();
+      for notification in mem::take(&mut *self.inbox.lock()) {
+        match notification {
+          Notification::Inserted(hash) => {
+            if let Some(event) = block_event(consensus, &hash) {
+              listeners.block.notify(event);
+            }
+          }
+          Notification::Ordered(epoch, block_hashes) => {
+            changes.extend(cursor.order(epoch, block_hashes));
+          }
+        }
+      }
+      changes.extend(cursor.execute(consensus));
+
+      // The pool content is only read once its counters moved or the chain
+      // changed.
+      match listeners.transaction.is_set() {
+        true => {
+          let stats = txpool.stats();
+          let tracker = transactions.get_or_insert_with(|| TransactionTracker::new(txpool));
+          if !changes.is_empty() || last_stats != Some(stats) {
+            last_stats = Some(stats);
+            tracker.update(consensus, txpool, &changes, &listeners.transaction);
+          }
+        }
+        false => {
+          transactions = None;
+          last_stats = None;
+        }
+      }
+
+      self.deliver_logs(consensus, &changes, cursor.executed());
+
+      for change in changes {
+        if let ChainChange::Executed(epoch, block_hashes) = change {
+          if let Some(event) = epoch_event(consensus, epoch, &block_hashes) {
+            listeners.epoch.notify(event);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// A step of the chain, in the order it happened.
+pub enum ChainChange {
+  /// An executed epoch left the pivot chain.
+  Reverted(u64),
+  /// An epoch joined the pivot chain, with its blocks in execution order.
+  Mined(u64, Vec<H256>),
+  /// An epoch was executed.
+  Executed(u64, Vec<H256>),
+}
+
+/// The epochs of the pivot chain that are not executed yet.
+pub struct ChainCursor {
+  /// The latest epoch that joined the pivot chain.
+  ordered: u64,
+  /// The blocks of each unexecuted epoch, the pivot block last.
+  unexecuted: BTreeMap<u64, Vec<H256>>,
+  executed: u64,
+}
+
+impl ChainCursor {
+  pub fn new(consensus: &ConsensusGraph) -> Self {
+    let ordered = consensus.best_epoch_number();
+    let executed = consensus.best_executed_state_epoch_number();
+    let unexecuted = (executed + 1..=ordered)
+      .filter_map(|epoch| Some((epoch, epoch_blocks(consensus, epoch)?)))
+      .collect();
+    ChainCursor {
+      ordered,
+      unexecuted,
+      executed,
+    }
+  }
+
+  /// The latest epoch reported as executed.
+  pub fn executed(&self) -> u64 {
+    self.executed
+  }
+
+  /// Whether an epoch is far enough behind the best one to be executed, but
+  /// was not reported as executed yet.
+  fn awaits_execution(&self, consensus: &ConsensusGraph) -> bool {
+    let due = consensus
+      .best_epoch_number()
+      .saturating_sub(DEFERRED_STATE_EPOCH_COUNT);
+    due > self.executed
+  }
+
+  /// Take an epoch that joined the pivot chain. An epoch that was ordered
+  /// before means the pivot chain switched there.
+  pub fn order(&mut self, epoch: u64, block_hashes: Vec<H256>) -> Vec<ChainChange> {
+    // Announced again while the cursor started.
+    if self.unexecuted.get(&epoch) == Some(&block_hashes) {
+      return Vec::new();
+    }
+
+    let mut changes = Vec::new();
+    if epoch <= self.ordered {
+      for reverted in (epoch..=self.executed).rev() {
+        changes.push(ChainChange::Reverted(reverted));
+      }
+      self.executed = self.executed.min(epoch.saturating_sub(1));
+      self.unexecuted.split_off(&epoch);
+    }
+    self.ordered = epoch;
+    self.unexecuted.insert(epoch, block_hashes.clone());
+    changes.push(ChainChange::Mined(epoch, block_hashes));
+    changes
+  }
+
+  /// The epochs executed since the last call.
+  pub fn execute(&mut self, consensus: &ConsensusGraph) -> Vec<ChainChange> {
+    let executed = consensus
+      .best_executed_state_epoch_number()
+      .min(self.ordered);
+    let mut changes = Vec::new();
+    for epoch in self.executed + 1..=executed {
+      let Some(block_hashes) = self
+        .unexecuted
+        .remove(&epoch)
+        .or_else(|| epoch_blocks(consensus, epoch))
+      else {
+        break;
+      };
+      changes.push(ChainChange::Executed(epoch, block_hashes));
+      self.executed = epoch;
+    }
+    changes
+  }
+}
+
+fn pivot_hash(consensus: &ConsensusGraph, epoch: u64) -> Option<H256> {
+  consensus
+    .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+    .ok()
+}
+
+pub fn epoch_blocks(consensus: &ConsensusGraph, epoch: u64) -> Option<Vec<H256>> {
+  consensus
+    .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+    .ok()
+}
+
+fn block_event(consensus: &ConsensusGraph, hash: &H256) -> Option<BlockEvent> {
+  let header = consensus.data_manager().block_header_by_hash(hash)?;
+  let epoch = consensus.get_block_epoch_number(hash);
+  Some(BlockEvent {
+    hash: hex(hash),
+    height: BigInt::from(header.height()),
+    epoch_number: epoch.map(BigInt::from),
+    pivot: epoch.and_then(|epoch| pivot_hash(consensus, epoch)) == Some(*hash),
+    timestamp: BigInt::from(header.timestamp()),
+  })
+}
+
+fn epoch_event(
+  consensus: &ConsensusGraph,
+  epoch: u64,
+  block_hashes: &[H256],
+) -> Option<EpochEvent> {
+  let pivot = block_hashes.last()?;
+  let header = consensus.data_manager().block_header_by_hash(pivot)?;
+  Some(EpochEvent {
+    epoch_number: BigInt::from(epoch),
+    hash: hex(pivot),
+    height: BigInt::from(header.height()),
+    timestamp: BigInt::from(header.timestamp()),
+    block_hashes: block_hashes.iter().map(hex).collect(),
+  })
+}
+
+/// Callbacks for chain progress, fed straight from consensus without a port.
+#[napi]
+impl ConfluxNode {
+  /// Register a callback for every block inserted into the tree-graph.
+  /// Registering a new callback replaces the previous one.
+  #[napi]
+  pub fn on_block(&self, callback: JsCallback<BlockEvent>) {
+    self.chain_listeners.block.set(callback);
+  }
+
+  /// Register a callback for every executed epoch.
+  /// Registering a new callback replaces the previous one.
+  #[napi]
+  pub fn on_epoch(&self, callback: JsCallback<EpochEvent>) {
+    self.chain_listeners.epoch.set(callback);
+  }
+}
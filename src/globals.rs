@@ -0,0 +1,80 @@
+use crate::{
+  config::{resolve_yaml_path, write_resolved_yaml},
+  error::{NodeError, Result},
+};
+use parking_lot::{const_mutex, Mutex};
+use primitives::block_header::CIP112_TRANSITION_HEIGHT;
+use std::path::{Path, PathBuf};
+
+// log4rs can only be initialized once per process, so every node has to share
+// the first logging configuration that was installed.
+static LOG_CONF: Mutex<Option<PathBuf>> = const_mutex(None);
+
+/// Record the CIP-112 transition height for this process.
+///
+/// The height lives in a process-wide once-cell inside conflux, so a node may
+/// only start if it agrees with the height used by the nodes started before it.
+pub fn claim_cip112_transition_height(height: u64) -> Result<()> {
+  let current = *CIP112_TRANSITION_HEIGHT.get_or_init(|| height);
+  if current != height {
+    return Err(NodeError::Configuration(format!(
+      "cip112_transition_height {} conflicts with {} already used by another node in this process",
+      display_height(height),
+      display_height(current)
+    )));
+  }
+  Ok(())
+}
+
+/// Install the log4rs configuration, or check it matches the installed one.
+///
+/// Relative appender paths of a YAML configuration are resolved against
+/// `data_dir` in a copy of it, the working directory is shared by every node.
+pub fn init_logging(log_conf: &Path, data_dir: &Path) -> Result<()> {
+  let mut installed = LOG_CONF.lock();
+
+  if let Some(current) = installed.as_ref() {
+    if current != log_conf {
+      return Err(NodeError::Configuration(format!(
+        "log_conf {:?} conflicts with {:?} already used by another node in this process",
+        log_conf, current
+      )));
+    }
+    return Ok(());
+  }
+
+  let is_yaml = matches!(
+    log_conf.extension().and_then(|ext| ext.to_str()),
+    Some("yaml" | "yml")
+  );
+  // Other formats are installed as they are.
+  let resolved = match is_yaml {
+    false => log_conf.to_path_buf(),
+    true => write_resolved_yaml(log_conf, data_dir, "log.yaml", |doc| {
+      // The copy goes away with a temporary data dir, it must not be reloaded.
+      if let Some(conf) = doc.as_mapping_mut() {
+        conf.remove("refresh_rate");
+      }
+      let appenders = doc
+        .get_mut("appenders")
+        .and_then(serde_yaml::Value::as_mapping_mut);
+      for (_, appender) in appenders.into_iter().flatten() {
+        resolve_yaml_path(appender, &["path"], data_dir);
+        resolve_yaml_path(appender, &["policy", "roller", "pattern"], data_dir);
+      }
+    })?,
+  };
+  log4rs::init_file(&resolved, Default::default())
+    .map_err(|e| NodeError::Configuration(format!("Failed to initialize logging: {}", e)))?;
+
+  *installed = Some(log_conf.to_path_buf());
+  Ok(())
+}
+
+fn display_height(height: u64) -> String {
+  if height == u64::MAX {
+    "disabled".to_string()
+  } else {
+    height.to_string()
+  }
+}
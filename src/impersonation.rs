@@ -0,0 +1,220 @@
+use crate::{
+  cheats::parse_bytes,
+  components::NodeComponents,
+  error::{NodeError, Result},
+  events::ChainListeners,
+  query::{hex, parse_address, to_u256, to_u64, Space},
+  ConfluxNode,
+};
+use cfx_types::{Address, AddressSpaceUtil, AddressWithSpace, H256, U256};
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use primitives::{
+  transaction::{
+    Eip155Transaction, EthereumTransaction, NativeTransaction, TypedNativeTransaction,
+  },
+  Action, SignedTransaction, Transaction,
+};
+use std::{collections::HashSet, sync::Arc};
+
+const DEFAULT_GAS: u64 = 1_000_000;
+// 20 GDrip, above the minimum gas price of dev nodes in both spaces.
+const DEFAULT_GAS_PRICE: u64 = 20_000_000_000;
+const DEFAULT_STORAGE_LIMIT: u64 = 10_000;
+// `r` of the signature of impersonated transactions, `s` is the sender.
+const IMPERSONATION_MARKER: &[u8] = b"xcfx impersonated";
+
+#[napi(object)]
+pub struct UnsignedTransaction {
+  /// An impersonated address.
+  pub from: String,
+  pub space: Space,
+  /// `null` deploys a contract.
+  pub to: Option<String>,
+  pub value: Option<BigInt>,
+  pub data: Option<String>,
+  /// 1,000,000 by default.
+  pub gas: Option<BigInt>,
+  /// 20 GDrip by default.
+  pub gas_price: Option<BigInt>,
+  /// The next nonce of the sender in the pool by default.
+  pub nonce: Option<BigInt>,
+  /// Core space only, 10,000 bytes by default.
+  pub storage_limit: Option<BigInt>,
+}
+
+/// The senders a dev node accepts unsigned transactions from.
+pub struct Impersonation {
+  accounts: Mutex<HashSet<AddressWithSpace>>,
+  listeners: Arc<ChainListeners>,
+}
+
+impl Impersonation {
+  pub fn new(listeners: Arc<ChainListeners>) -> Self {
+    Impersonation {
+      accounts: Mutex::new(HashSet::new()),
+      listeners,
+    }
+  }
+
+  pub fn start(&self, address: AddressWithSpace) {
+    self.accounts.lock().insert(address);
+  }
+
+  pub fn stop(&self, address: &AddressWithSpace) -> bool {
+    self.accounts.lock().remove(address)
+  }
+
+  pub fn is_impersonated(&self, address: &AddressWithSpace) -> bool {
+    self.accounts.lock().contains(address)
+  }
+
+  /// Add a transaction from an impersonated sender to the pool.
+  pub fn send(
+    &self,
+    components: &NodeComponents,
+    transaction: UnsignedTransaction,
+  ) -> Result<H256> {
+    let from = parse_address(&transaction.from, transaction.space)?;
+    if !self.is_impersonated(&from) {
+      return Err(NodeError::Runtime(format!(
+        "{} is not impersonated",
+        transaction.from
+      )));
+    }
+
+    let consensus = components.consensus()?;
+    let txpool = components.txpool()?;
+    let action = match transaction.to.as_deref() {
+      Some(to) => Action::Call(parse_address(to, transaction.space)?.address),
+      None => Action::Create,
+    };
+    let nonce = match transaction.nonce.as_ref() {
+      Some(nonce) => to_u256(nonce)?,
+      None => txpool.get_next_nonce(&from),
+    };
+    let value = transaction.value.as_ref().map(to_u256).transpose()?;
+    let gas = transaction.gas.as_ref().map(to_u256).transpose()?;
+    let gas_price = transaction.gas_price.as_ref().map(to_u256).transpose()?;
+    let data = transaction
+      .data
+      .as_deref()
+      .map(parse_bytes)
+      .transpose()?
+      .unwrap_or_default();
+
+    let chain_id = consensus.best_chain_id();
+    let unsigned = match transaction.space {
+      Space::Native => Transaction::Native(TypedNativeTransaction::Cip155(NativeTransaction {
+        nonce,
+        gas_price: gas_price.unwrap_or(U256::from(DEFAULT_GAS_PRICE)),
+        gas: gas.unwrap_or(U256::from(DEFAULT_GAS)),
+        action,
+        value: value.unwrap_or_default(),
+        storage_limit: match transaction.storage_limit.as_ref() {
+          Some(limit) => to_u64(limit)?,
+          None => DEFAULT_STORAGE_LIMIT,
+        },
+        epoch_height: consensus.best_epoch_number(),
+        chain_id: chain_id.in_native_space(),
+        data,
+      })),
+      Space::Evm => Transaction::Ethereum(EthereumTransaction::Eip155(Eip155Transaction {
+        nonce,
+        gas_price: gas_price.unwrap_or(U256::from(DEFAULT_GAS_PRICE)),
+        gas: gas.unwrap_or(U256::from(DEFAULT_GAS)),
+        action,
+        value: value.unwrap_or_default(),
+        chain_id: Some(chain_id.in_evm_space()),
+        data,
+      })),
+    };
+
+    let signed = Arc::new(impersonated(unsigned, from));
+    let hash = signed.hash();
+    let (inserted, failures) = txpool.insert_new_signed_transactions(vec![signed]);
+    if inserted.is_empty() {
+      let reason = failures
+        .into_values()
+        .next()
+        .unwrap_or_else(|| "unknown reason".to_string());
+      self
+        .listeners
+        .transaction
+        .rejected(hash, Some(from), transaction.space, &reason);
+      return Err(NodeError::Runtime(format!(
+        "Transaction was not accepted: {}",
+        reason
+      )));
+    }
+    if let Ok(sync) = components.sync() {
+      sync.append_received_transactions(inserted);
+    }
+    Ok(hash)
+  }
+}
+
+/// Sign `unsigned` as `from` without its key. The sender goes into the
+/// signature, so equal transactions of different senders have different
+/// hashes and the sender can be told after the block was read back from the
+/// database.
+fn impersonated(unsigned: Transaction, from: AddressWithSpace) -> SignedTransaction {
+  let mut signed = unsigned.fake_sign(from);
+  let mut transaction = signed.transaction.clone();
+  transaction.transaction.r = U256::from_big_endian(IMPERSONATION_MARKER);
+  transaction.transaction.s = U256::from_big_endian(from.address.as_bytes());
+  signed.transaction = transaction.compute_hash();
+  signed
+}
+
+/// The sender of `tx`. Senders of blocks read from the database are recovered
+/// from the signature, which doesn't work for impersonated transactions, so
+/// theirs is taken from the signature as is.
+pub fn sender(tx: &SignedTransaction) -> AddressWithSpace {
+  let signature = &tx.transaction.transaction;
+  if signature.r != U256::from_big_endian(IMPERSONATION_MARKER) {
+    return tx.sender();
+  }
+  let mut bytes = [0u8; 32];
+  signature.s.to_big_endian(&mut bytes);
+  Address::from_slice(&bytes[12..]).with_space(tx.space())
+}
+
+/// Sending transactions as addresses without their private keys.
+///
+/// Transactions of impersonated senders skip signature recovery, the pool and
+/// the executor take the sender as given. Blocks with such transactions can't
+/// be verified by other nodes, so this is only available on dev mode nodes.
+#[napi]
+impl ConfluxNode {
+  /// Accept unsigned transactions from `address` until
+  /// `stop_impersonating_account` is called.
+  #[napi]
+  pub async fn impersonate_account(&self, address: String, space: Space) -> Result<()> {
+    self.dev_components().await?;
+    let address = parse_address(&address, space)?;
+    self.impersonation().await?.start(address);
+    Ok(())
+  }
+
+  /// Resolves with `false` if `address` was not impersonated.
+  #[napi]
+  pub async fn stop_impersonating_account(&self, address: String, space: Space) -> Result<bool> {
+    let address = parse_address(&address, space)?;
+    Ok(self.impersonation().await?.stop(&address))
+  }
+
+  /// Add a transaction from an impersonated sender to the pool. Resolves with
+  /// the transaction hash, with automine on once the transaction is packed.
+  #[napi]
+  pub async fn send_unsigned_transaction(
+    &self,
+    transaction: UnsignedTransaction,
+  ) -> Result<String> {
+    let components = self.dev_components().await?;
+    let hash = self.impersonation().await?.send(&components, transaction)?;
+    self.automine_sent().await?;
+    Ok(hex(&hash))
+  }
+}
@@ -3,7 +3,9 @@ use log::{info, warn};
 use napi::tokio::{
   sync::{oneshot, Mutex as TokioMutex},
   task,
+  time::{self, Instant},
 };
+use napi::{bindgen_prelude::PromiseRaw, Env};
 use napi_derive::napi;
 
 use cfx_config::Configuration;
@@ -15,148 +17,773 @@ use client::{
   light::LightClient,
 };
 use parking_lot::{Condvar, Mutex};
-use std::{env, fs, path::Path, sync::Arc};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  thread,
+  time::Duration,
+};
 use tempfile::{tempdir, TempDir};
+mod anvil;
+mod background;
+mod callback;
+mod cheats;
+mod clock;
+mod components;
 mod config;
+mod console;
+mod crash;
 mod error;
+mod events;
+mod globals;
+mod impersonation;
+mod logs;
+mod mining;
+mod query;
+mod readiness;
+mod rpc;
+mod snapshot;
+mod startup;
+mod status;
+mod transactions;
+mod txpool;
+mod watchdog;
+use anvil::{AnvilPorts, AnvilServer, DevContext};
+use callback::{CallbackSlot, JsCallback};
+use clock::Clock;
+use components::NodeComponents;
+use console::{ConsoleLog, ConsoleWatcher};
+use crash::{CrashMonitor, CrashReport};
 use error::{NodeError, Result};
-
+use events::{ChainListeners, ChainWatcher};
+use impersonation::Impersonation;
+use mining::MiningController;
+use rpc::{sends_raw_transaction, InProcessRpc, RpcCall, RpcResponse, RpcSettings};
+use snapshot::Snapshots;
+use startup::{StartAbort, StartCanceller};
+use status::{NodeState, NodeStatus, StateTransition, StatusTracker};
+use watchdog::{Heartbeat, Watchdog};
+
+type ExitSign = Arc<(Mutex<bool>, Condvar)>;
+
+/// How long the shutdown of a dropped `ConfluxNode` waits for the client
+/// before the node thread is abandoned.
+const DROP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+const BLOCKING_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Everything owned by one run of the node. A new lifecycle is built on every
+/// `start_node`, so nothing from a previous run leaks into the next one.
 struct NodeLifecycle {
-  thread_handle: task::JoinHandle<()>,
+  thread_handle: task::JoinHandle<bool>,
   shutdown_sender: oneshot::Sender<()>,
-  _temp_dir: Option<TempDir>,
+  exit_sign: ExitSign,
+  // Tells the exit watcher that the exit was requested, not a crash.
+  stopping: Arc<AtomicBool>,
+  components: NodeComponents,
+  config: config::ConfluxConfig,
+  data_dir: PathBuf,
+  temp_dir: Option<TempDir>,
+  watchdog: Option<Watchdog>,
+  rpc: Option<Arc<InProcessRpc>>,
+  mining: Option<Arc<MiningController>>,
+  clock: Arc<Clock>,
+  snapshots: Arc<Snapshots>,
+  dev_mode: bool,
+  impersonation: Arc<Impersonation>,
+  anvil: Option<AnvilServer>,
+  console: Option<Arc<ConsoleWatcher>>,
+  chain: Option<Arc<ChainWatcher>>,
 }
 
 impl NodeLifecycle {
-  fn new(
-    thread_handle: task::JoinHandle<()>,
-    shutdown_sender: oneshot::Sender<()>,
-    _temp_dir: Option<TempDir>,
-  ) -> Self {
-    NodeLifecycle {
-      thread_handle,
-      shutdown_sender,
-      _temp_dir,
-    }
-  }
-
-  async fn shutdown(self) -> Result<()> {
+  /// Stops the client and hands back the temp dir, so a restart can reuse it.
+  ///
+  /// If the client doesn't finish within `timeout` its thread is abandoned, the
+  /// temp dir is then released in the background once the thread ends.
+  async fn shutdown(
+    mut self,
+    timeout: Option<Duration>,
+  ) -> Result<(ShutdownReport, Option<TempDir>)> {
+    let started_at = Instant::now();
+    let last_executed_epoch = self
+      .components
+      .consensus()
+      .ok()
+      .map(|consensus| consensus.best_executed_state_epoch_number() as i64);
+
+    self.request_exit();
     let _ = self.shutdown_sender.send(());
 
-    match self.thread_handle.await {
-      Ok(_) => {
+    let joined = match timeout {
+      Some(timeout) => time::timeout(timeout, &mut self.thread_handle).await.ok(),
+      None => Some((&mut self.thread_handle).await),
+    };
+
+    let report = |db_flushed, forced| ShutdownReport {
+      elapsed_ms: started_at.elapsed().as_millis() as i64,
+      db_flushed,
+      last_executed_epoch,
+      forced,
+    };
+
+    match joined {
+      Some(Ok(db_flushed)) => {
         info!("Node thread completed successfully");
-        Ok(())
+        Ok((report(db_flushed, false), self.temp_dir))
       }
-      Err(e) if e.is_cancelled() => Ok(()),
-      Err(e) if e.is_panic() => {
+      Some(Err(e)) if e.is_cancelled() => Ok((report(false, false), self.temp_dir)),
+      Some(Err(e)) if e.is_panic() => {
         warn!("Node thread panicked: {:?}", e);
         Err(NodeError::Shutdown("Node thread panicked".to_string()))
       }
-      Err(e) => Err(NodeError::Shutdown(format!(
+      Some(Err(e)) => Err(NodeError::Shutdown(format!(
         "Failed to wait for node thread: {}",
         e
       ))),
+      None => {
+        warn!("Node did not shut down in time, abandoning the node thread");
+        let thread_handle = self.thread_handle;
+        let temp_dir = self.temp_dir;
+        task::spawn(async move {
+          let _ = thread_handle.await;
+          drop(temp_dir);
+        });
+        Ok((report(false, true), None))
+      }
+    }
+  }
+
+  /// Blocking variant of `shutdown` for places without an async context, such
+  /// as the thread `Drop` hands the lifecycle to. Errors are only logged.
+  fn shutdown_blocking(mut self, timeout: Duration) {
+    self.request_exit();
+    let _ = self.shutdown_sender.send(());
+
+    let deadline = std::time::Instant::now() + timeout;
+    while !self.thread_handle.is_finished() {
+      if std::time::Instant::now() >= deadline {
+        warn!("Node did not shut down in time, abandoning the node thread");
+        let thread_handle = self.thread_handle;
+        let temp_dir = self.temp_dir;
+        let spawned = thread::Builder::new()
+          .name("xcfx-temp-dir-reaper".into())
+          .spawn(move || {
+            while !thread_handle.is_finished() {
+              thread::sleep(BLOCKING_POLL_INTERVAL);
+            }
+            drop(temp_dir);
+          });
+        if let Err(e) = spawned {
+          warn!("Failed to spawn the temp dir reaper: {}", e);
+        }
+        return;
+      }
+      thread::sleep(BLOCKING_POLL_INTERVAL);
     }
+    info!("Node thread completed successfully");
   }
+
+  /// Tell the exit watcher the exit is intended and wake up the client.
+  fn request_exit(&mut self) {
+    if let Some(mining) = &self.mining {
+      mining.stop();
+    }
+    if let Some(console) = &self.console {
+      console.stop();
+    }
+    if let Some(chain) = &self.chain {
+      chain.stop();
+    }
+    // The servers and the in-process modules hold on to the client components.
+    self.anvil.take();
+    if let Some(rpc) = self.rpc.take() {
+      rpc.close();
+    }
+    self.stopping.store(true, Ordering::SeqCst);
+    *self.exit_sign.0.lock() = true;
+    self.exit_sign.1.notify_all();
+  }
+}
+
+#[napi(object)]
+pub struct StopOptions {
+  /// How long to wait for a clean shutdown before the node thread is abandoned.
+  /// If not set, `stop_node` waits until the shutdown completes.
+  ///
+  /// The abandoned thread keeps shutting down in the background, ports and
+  /// data directory stay in use until it is done.
+  pub timeout_ms: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct ShutdownReport {
+  pub elapsed_ms: i64,
+  /// Whether every database handle was released, so the data was flushed.
+  pub db_flushed: bool,
+  /// The last executed epoch at the time the shutdown started.
+  pub last_executed_epoch: Option<i64>,
+  /// Whether the clean shutdown missed the deadline and the node thread was
+  /// abandoned.
+  ///
+  /// An abandoned client can't be torn down from outside its thread, it keeps
+  /// its ports and data directory until the shutdown finishes on its own. A
+  /// new start on the same ports or data directory fails until then.
+  pub forced: bool,
 }
 
+#[napi(object)]
+pub struct RestartResult {
+  /// The best epoch number the chain resumed from.
+  pub resumed_epoch: i64,
+}
+
+/// A Conflux node that can be started and stopped any number of times.
+///
+/// `start_node` is only allowed while the node is not running and `stop_node`
+/// only while it is. Every start builds a fresh exit signal and, when no data
+/// directory is configured, a fresh temp directory.
 #[napi]
 pub struct ConfluxNode {
   lifecycle: Arc<TokioMutex<Option<NodeLifecycle>>>,
-  exit_sign: Arc<(Mutex<bool>, Condvar)>,
+  status: Arc<StatusTracker>,
+  crash_monitor: Arc<CrashMonitor>,
+  start_canceller: StartCanceller,
+  heartbeat: Arc<Heartbeat>,
+  console_listener: Arc<CallbackSlot<ConsoleLog>>,
+  chain_listeners: Arc<ChainListeners>,
+}
+
+/// What starting a node needs from `ConfluxNode`, owned so the start can run
+/// detached from the JS call that requested it.
+#[derive(Clone)]
+struct Launcher {
+  lifecycle: Arc<TokioMutex<Option<NodeLifecycle>>>,
+  status: Arc<StatusTracker>,
+  crash_monitor: Arc<CrashMonitor>,
+  heartbeat: Arc<Heartbeat>,
+  console_listener: Arc<CallbackSlot<ConsoleLog>>,
+  chain_listeners: Arc<ChainListeners>,
 }
 
 #[napi]
 impl ConfluxNode {
   #[napi(constructor)]
   pub fn new() -> Self {
+    let status = Arc::new(StatusTracker::default());
     ConfluxNode {
       lifecycle: Arc::new(TokioMutex::new(None)),
-      exit_sign: Arc::new((Mutex::new(false), Condvar::new())),
+      crash_monitor: CrashMonitor::new(status.clone()),
+      status,
+      start_canceller: Arc::new(Mutex::new(None)),
+      heartbeat: Arc::new(Heartbeat::default()),
+      console_listener: Arc::new(CallbackSlot::default()),
+      chain_listeners: Arc::new(ChainListeners::default()),
+    }
+  }
+
+  /// The start can be cancelled with `cancel_start` as soon as this returns,
+  /// before any of the async work began.
+  #[napi(ts_return_type = "Promise<void>")]
+  pub fn start_node<'env>(
+    &self,
+    env: &'env Env,
+    config: config::ConfluxConfig,
+  ) -> napi::Result<PromiseRaw<'env, ()>> {
+    let abort = StartAbort::arm(&self.start_canceller, config.startup_timeout());
+    let launcher = self.launcher();
+    env.spawn_future(async move { Ok(launcher.start(config, abort?).await?) })
+  }
+
+  /// Stop the node and boot it again on the same data directory.
+  ///
+  /// Ports, chain data and the node identity are kept, including an
+  /// automatically created temp directory. Fields set in `overrides` replace
+  /// the ones the node was started with.
+  #[napi]
+  pub async fn restart_node(
+    &self,
+    overrides: Option<config::ConfluxConfig>,
+  ) -> Result<RestartResult> {
+    let mut lifecycle_guard = self.lifecycle.lock().await;
+
+    let lifecycle = match lifecycle_guard.take() {
+      Some(lifecycle) => lifecycle,
+      None => return Err(NodeError::Runtime("Node is not running".to_string())),
+    };
+
+    if let Some(dir) = overrides.as_ref().and_then(|o| o.conflux_data_dir.as_ref()) {
+      if std::path::absolute(dir).ok().as_ref() != Some(&lifecycle.data_dir) {
+        *lifecycle_guard = Some(lifecycle);
+        return Err(NodeError::Configuration(
+          "restart_node keeps the data directory, conflux_data_dir can't be changed".to_string(),
+        ));
+      }
     }
+
+    let config = match overrides {
+      Some(overrides) => lifecycle.config.clone().merge(overrides),
+      None => lifecycle.config.clone(),
+    };
+    let data_dir = lifecycle.data_dir.clone();
+    let abort = match StartAbort::arm(&self.start_canceller, config.startup_timeout()) {
+      Ok(abort) => abort,
+      Err(e) => {
+        *lifecycle_guard = Some(lifecycle);
+        return Err(e);
+      }
+    };
+
+    self.status.transition(NodeState::Stopping);
+    let (_, temp_dir) = lifecycle
+      .shutdown(None)
+      .await
+      .inspect_err(|e| self.status.fail(e))?;
+    self.status.transition(NodeState::Stopped);
+    info!("Node stopped for restart");
+
+    self.status.transition(NodeState::Starting);
+    let launcher = self.launcher();
+    let lifecycle = launcher
+      .launch(config, data_dir, temp_dir, abort)
+      .await
+      .inspect_err(|e| launcher.record_start_error(e))?;
+    let resumed_epoch = lifecycle
+      .components
+      .consensus()
+      .map(|c| c.best_epoch_number());
+    *lifecycle_guard = Some(lifecycle);
+    self.status.transition(NodeState::Running);
+    let resumed_epoch = resumed_epoch?;
+
+    info!("Node restarted from epoch {}", resumed_epoch);
+
+    Ok(RestartResult {
+      resumed_epoch: resumed_epoch as i64,
+    })
+  }
+
+  /// Stop the node and resolve with how the shutdown went.
+  ///
+  /// With `timeout_ms` the call resolves once the deadline passed, even if the
+  /// client is still shutting down. The report is `forced` then, and the
+  /// client holds on to its ports and data directory until it is done.
+  #[napi]
+  pub async fn stop_node(&self, options: Option<StopOptions>) -> Result<ShutdownReport> {
+    let timeout = options
+      .and_then(|options| options.timeout_ms)
+      .map(|ms| Duration::from_millis(ms as u64));
+    self
+      .stop(timeout)
+      .await?
+      .ok_or_else(|| NodeError::Runtime("Node is not running".to_string()))
+  }
+
+  /// Stop the node if it is running or starting, and do nothing otherwise.
+  ///
+  /// Backs `Symbol.asyncDispose`, so it never fails just because the node is
+  /// already stopped. Returns `null` if there was nothing to stop.
+  #[napi]
+  pub async fn dispose(&self) -> Result<Option<ShutdownReport>> {
+    self.cancel_start();
+    self.stop(None).await
+  }
+
+  /// Send a JSON-RPC request straight to the node, without going through a port.
+  ///
+  /// Resolves with the `result` of the call and rejects with the JSON-RPC error.
+  /// With automine on, sent raw transactions are packed before it resolves.
+  #[napi]
+  pub async fn request(
+    &self,
+    method: String,
+    params: Option<serde_json::Value>,
+  ) -> Result<serde_json::Value> {
+    let response = self.call(RpcCall { method, params }).await?;
+    match response.error {
+      Some(error) => Err(NodeError::Rpc(format!(
+        "{} (code {})",
+        error.message, error.code
+      ))),
+      None => Ok(response.result.unwrap_or_default()),
+    }
+  }
+
+  /// Send several JSON-RPC requests in order. A failing call doesn't fail the
+  /// batch, its response carries the `error` instead.
+  #[napi]
+  pub async fn request_batch(&self, calls: Vec<RpcCall>) -> Result<Vec<RpcResponse>> {
+    let mut responses = Vec::with_capacity(calls.len());
+    for call in calls {
+      responses.push(self.call(call).await?);
+    }
+    Ok(responses)
+  }
+
+  /// The current lifecycle state, when it was entered and the last error.
+  #[napi]
+  pub fn get_status(&self) -> NodeStatus {
+    self.status.snapshot()
+  }
+
+  /// Register a callback that is invoked on every state transition.
+  /// Registering a new callback replaces the previous one.
+  #[napi]
+  pub fn on_state_change(&self, callback: JsCallback<StateTransition>) {
+    self.status.set_listener(callback);
   }
 
+  /// Register a callback for panics and unexpected exits of the node.
+  /// Panics on conflux threads are reported to every node in the process.
   #[napi]
-  pub async fn start_node(&self, config: config::ConfluxConfig) -> Result<()> {
+  pub fn on_crash(&self, callback: JsCallback<CrashReport>) {
+    self.crash_monitor.set_listener(callback);
+  }
+
+  /// Register a callback for `console.log` calls of eSpace contracts on dev
+  /// mode nodes. They are also written to the log.
+  /// Register it before `startNode`, the calls are only traced when a callback
+  /// is set at start. Nodes started from a config file need
+  /// `executive_trace = true` in it.
+  /// Registering a new callback replaces the previous one.
+  #[napi]
+  pub fn on_console_log(&self, callback: JsCallback<ConsoleLog>) {
+    self.console_listener.set(callback);
+  }
+
+  /// Tell the watchdog the host is still alive.
+  ///
+  /// Only needed when `watchdog_timeout_ms` is configured, the node shuts
+  /// itself down once no heartbeat arrived within that interval.
+  #[napi]
+  pub fn heartbeat(&self) {
+    self.heartbeat.beat();
+  }
+
+  /// Cancel a pending `start_node` or `restart_node`.
+  ///
+  /// The half-started node is torn down and the pending call fails with a
+  /// cancellation error. A cancel right after `start_node` was called also
+  /// counts, even if the start did not get to do anything yet. Returns `false`
+  /// if no start was in progress.
+  #[napi]
+  pub fn cancel_start(&self) -> bool {
+    match self.start_canceller.lock().take() {
+      Some(canceller) => canceller.send(true).is_ok(),
+      None => false,
+    }
+  }
+
+  /// Run `f` on the state of the current run.
+  async fn with_lifecycle<T>(&self, f: impl FnOnce(&NodeLifecycle) -> Result<T>) -> Result<T> {
+    let lifecycle = self.lifecycle.lock().await;
+    let lifecycle = lifecycle
+      .as_ref()
+      .ok_or_else(|| NodeError::Runtime("Node is not running".to_string()))?;
+    f(lifecycle)
+  }
+
+  async fn components(&self) -> Result<NodeComponents> {
+    self
+      .with_lifecycle(|lifecycle| Ok(lifecycle.components.clone()))
+      .await
+  }
+
+  async fn dev_components(&self) -> Result<NodeComponents> {
+    self
+      .with_lifecycle(|lifecycle| match lifecycle.dev_mode {
+        true => Ok(lifecycle.components.clone()),
+        false => Err(NodeError::Runtime(
+          "This method is only available on dev mode nodes".to_string(),
+        )),
+      })
+      .await
+  }
+
+  async fn mining(&self) -> Result<Arc<MiningController>> {
+    self
+      .with_lifecycle(|lifecycle| {
+        lifecycle.mining.clone().ok_or_else(|| {
+          NodeError::Runtime(
+            "Block production can only be controlled on dev mode nodes".to_string(),
+          )
+        })
+      })
+      .await
+  }
+
+  async fn clock(&self) -> Result<Arc<Clock>> {
+    self
+      .with_lifecycle(|lifecycle| Ok(lifecycle.clock.clone()))
+      .await
+  }
+
+  async fn impersonation(&self) -> Result<Arc<Impersonation>> {
+    self
+      .with_lifecycle(|lifecycle| Ok(lifecycle.impersonation.clone()))
+      .await
+  }
+
+  async fn snapshots(&self) -> Result<Arc<Snapshots>> {
+    self
+      .with_lifecycle(|lifecycle| Ok(lifecycle.snapshots.clone()))
+      .await
+  }
+
+  async fn chain_watcher(&self) -> Result<Arc<ChainWatcher>> {
+    self
+      .with_lifecycle(|lifecycle| {
+        lifecycle.chain.clone().ok_or_else(|| {
+          NodeError::Runtime(
+            "Light nodes don't execute transactions, they have no logs".to_string(),
+          )
+        })
+      })
+      .await
+  }
+
+  async fn rpc(&self) -> Result<Arc<InProcessRpc>> {
+    self
+      .with_lifecycle(|lifecycle| {
+        lifecycle
+          .rpc
+          .clone()
+          .ok_or_else(|| NodeError::Runtime("Node is not running".to_string()))
+      })
+      .await
+  }
+
+  async fn call(&self, call: RpcCall) -> Result<RpcResponse> {
+    let sends = sends_raw_transaction(&call.method);
+    let response = self.rpc().await?.call(call).await?;
+    if sends && response.error.is_none() {
+      self.automine_sent().await?;
+    }
+    Ok(response)
+  }
+
+  async fn stop(&self, timeout: Option<Duration>) -> Result<Option<ShutdownReport>> {
+    stop_lifecycle(&self.lifecycle, &self.status, timeout).await
+  }
+
+  fn launcher(&self) -> Launcher {
+    Launcher {
+      lifecycle: self.lifecycle.clone(),
+      status: self.status.clone(),
+      crash_monitor: self.crash_monitor.clone(),
+      heartbeat: self.heartbeat.clone(),
+      console_listener: self.console_listener.clone(),
+      chain_listeners: self.chain_listeners.clone(),
+    }
+  }
+}
+
+impl Launcher {
+  async fn start(&self, config: config::ConfluxConfig, abort: StartAbort) -> Result<()> {
     let mut lifecycle_guard = self.lifecycle.lock().await;
 
     if lifecycle_guard.is_some() {
       return Err(NodeError::Runtime("Node is already running".to_string()));
     }
 
-    let (data_dir, temp_dir) = self.prepare_data_directory(&config)?;
+    self.status.transition(NodeState::Starting);
 
-    let conf = self.setup_configuration(&config, &data_dir)?;
-
-    let lifecycle = self.spawn_node_async(conf, temp_dir).await?;
+    let lifecycle = async {
+      let (data_dir, temp_dir) = self.prepare_data_directory(&config)?;
+      self.launch(config, data_dir, temp_dir, abort).await
+    }
+    .await
+    .inspect_err(|e| self.record_start_error(e))?;
     *lifecycle_guard = Some(lifecycle);
+    self.status.transition(NodeState::Running);
 
     info!("Node started successfully");
 
     Ok(())
   }
 
-  #[napi]
-  pub async fn stop_node(&self) -> Result<()> {
-    let lifecycle = {
-      let mut lifecycle_guard = self.lifecycle.lock().await;
-      match lifecycle_guard.take() {
-        Some(lifecycle) => lifecycle,
-        None => return Err(NodeError::Runtime("Node is not running".to_string())),
-      }
+  fn record_start_error(&self, e: &NodeError) {
+    match e {
+      NodeError::Cancelled(_) => self.status.transition(NodeState::Stopped),
+      _ => self.status.fail(e),
+    }
+  }
+
+  async fn launch(
+    &self,
+    config: config::ConfluxConfig,
+    data_dir: PathBuf,
+    temp_dir: Option<TempDir>,
+    mut abort: StartAbort,
+  ) -> Result<NodeLifecycle> {
+    let mut conf = self.setup_configuration(&config, &data_dir)?;
+    let console_log = conf.is_dev_mode() && self.console_listener.is_set();
+    if console_log {
+      config.trace_console_logs(&mut conf.raw_conf)?;
+    }
+    let mining_mode = MiningController::take_mode(&mut conf);
+    let anvil_ports = AnvilPorts::take(&mut conf, config.anvil_rpc_enabled());
+    let rpc_ports = readiness::rpc_ports(&conf);
+    let ready_timeout = config.ready_timeout();
+    let watchdog_timeout = config.watchdog_timeout();
+    let rpc_settings = RpcSettings::from_conf(&conf);
+
+    let mut lifecycle = self
+      .spawn_node_async(conf, config, data_dir, temp_dir, &mut abort)
+      .await?;
+
+    let ready = napi::tokio::select! {
+      ready = readiness::wait_until_ready(&lifecycle.components, &rpc_ports, ready_timeout) => ready,
+      e = abort.wait() => Err(e),
+    };
+    let context = DevContext {
+      components: lifecycle.components.clone(),
+      clock: lifecycle.clock.clone(),
+      snapshots: lifecycle.snapshots.clone(),
+      impersonation: lifecycle.impersonation.clone(),
     };
+    let rpc = ready
+      .and_then(|_| InProcessRpc::build(&rpc_settings, &context, self.chain_listeners.clone()));
+
+    match rpc {
+      Ok(rpc) => lifecycle.rpc = Some(Arc::new(rpc)),
+      Err(e) => {
+        warn!("Node failed to become ready, shutting it down: {}", e);
+        let _ = lifecycle.shutdown(None).await;
+        return Err(e);
+      }
+    }
 
-    *self.exit_sign.0.lock() = true;
-    self.exit_sign.1.notify_all();
+    if let Some(ports) = anvil_ports {
+      match AnvilServer::start(ports, &rpc_settings, context).await {
+        Ok(server) => lifecycle.anvil = Some(server),
+        Err(e) => {
+          warn!(
+            "Failed to serve the dev RPC methods, shutting the node down: {}",
+            e
+          );
+          let _ = lifecycle.shutdown(None).await;
+          return Err(e);
+        }
+      }
+    }
 
-    lifecycle.shutdown().await?;
-    info!("Node shutdown complete");
+    lifecycle.mining = mining_mode
+      .filter(|_| lifecycle.components.blockgen().is_ok())
+      .map(|mode| {
+        MiningController::start(mode, lifecycle.components.clone(), lifecycle.clock.clone())
+      });
+    if console_log {
+      lifecycle.console =
+        ConsoleWatcher::start(lifecycle.components.clone(), self.console_listener.clone());
+    }
+    lifecycle.chain =
+      ChainWatcher::start(lifecycle.components.clone(), self.chain_listeners.clone());
+    lifecycle.watchdog = watchdog_timeout.map(|timeout| self.arm_watchdog(timeout));
+    Ok(lifecycle)
+  }
 
-    Ok(())
+  /// The watchdog stops the node the same way `stop_node` does.
+  fn arm_watchdog(&self, timeout: Duration) -> Watchdog {
+    let lifecycle = self.lifecycle.clone();
+    let status = self.status.clone();
+    Watchdog::arm(self.heartbeat.clone(), timeout, async move {
+      if let Err(e) = stop_lifecycle(&lifecycle, &status, None).await {
+        warn!("Watchdog failed to stop the node: {}", e);
+      }
+    })
   }
 
   async fn spawn_node_async(
     &self,
     conf: Configuration,
+    config: config::ConfluxConfig,
+    data_dir: PathBuf,
     temp_dir: Option<TempDir>,
+    abort: &mut StartAbort,
   ) -> Result<NodeLifecycle> {
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
     let (startup_status_tx, startup_status_rx) =
-      oneshot::channel::<std::result::Result<(), NodeError>>();
+      oneshot::channel::<std::result::Result<NodeComponents, NodeError>>();
 
-    let exit_sign = self.exit_sign.clone();
+    let exit_sign: ExitSign = Arc::new((Mutex::new(false), Condvar::new()));
+    let client_exit_sign = exit_sign.clone();
+    let dev_mode = conf.is_dev_mode();
 
     let join_handle = task::spawn_blocking(move || {
-      let client_result = ConfluxNode::create_client(conf, exit_sign);
+      let client_result = Launcher::create_client(conf, client_exit_sign);
 
       match client_result {
-        Ok(client) => {
-          if startup_status_tx.send(Ok(())).is_err() {
+        Ok((client, components)) => {
+          if startup_status_tx.send(Ok(components)).is_err() {
             warn!("Failed to send successful startup signal to start_node; main task might have been cancelled. Shutting down node.");
-            shutdown(client);
-            return;
+            return shutdown(client);
           }
 
           // Wait for shutdown signal
           let _ = shutdown_rx.blocking_recv();
 
           info!("Node is shutting down");
-          shutdown(client);
+          let db_flushed = shutdown(client);
           info!("Node shutdown complete");
+          db_flushed
         }
         Err(e) => {
           if startup_status_tx.send(Err(e)).is_err() {
             warn!("Failed to send startup error signal to start_node; error may not be propagated to caller.");
           }
+          false
         }
       }
     });
 
-    match startup_status_rx.await {
-      Ok(Ok(())) => {
-        let thread_handle = join_handle;
-        Ok(NodeLifecycle::new(thread_handle, shutdown_tx, temp_dir))
+    let startup_status = napi::tokio::select! {
+      status = startup_status_rx => status,
+      e = abort.wait() => {
+        warn!("Giving up on node startup: {}", e);
+        // Dropping the startup receiver makes the client thread shut the client
+        // down as soon as `start` returns, the temp dir is released after that.
+        *exit_sign.0.lock() = true;
+        exit_sign.1.notify_all();
+        task::spawn(async move {
+          let _ = join_handle.await;
+          drop(temp_dir);
+        });
+        return Err(e);
+      }
+    };
+
+    match startup_status {
+      Ok(Ok(components)) => {
+        let stopping = Arc::new(AtomicBool::new(false));
+        self
+          .crash_monitor
+          .watch_exit(exit_sign.clone(), stopping.clone());
+
+        Ok(NodeLifecycle {
+          thread_handle: join_handle,
+          shutdown_sender: shutdown_tx,
+          exit_sign,
+          stopping,
+          components,
+          config,
+          data_dir,
+          temp_dir,
+          watchdog: None,
+          rpc: None,
+          mining: None,
+          clock: Arc::new(Clock::default()),
+          snapshots: Arc::new(Snapshots::default()),
+          dev_mode,
+          impersonation: Arc::new(Impersonation::new(self.chain_listeners.clone())),
+          anvil: None,
+          console: None,
+          chain: None,
+        })
       }
       Ok(Err(e)) => {
         join_handle.abort();
@@ -173,17 +800,26 @@ impl ConfluxNode {
 
   fn create_client(
     conf: Configuration,
-    exit_sign: Arc<(Mutex<bool>, Condvar)>,
-  ) -> Result<Box<dyn ClientTrait>> {
+    exit_sign: ExitSign,
+  ) -> Result<(Box<dyn ClientTrait>, NodeComponents)> {
     match conf.node_type() {
       NodeType::Archive => ArchiveClient::start(conf, exit_sign)
-        .map(|client| client as Box<dyn ClientTrait>)
+        .map(|client| {
+          let components = NodeComponents::from_archive(&client);
+          (client as Box<dyn ClientTrait>, components)
+        })
         .map_err(|e| NodeError::Runtime(format!("Failed to start Archive node: {}", e))),
       NodeType::Full => FullClient::start(conf, exit_sign)
-        .map(|client| client as Box<dyn ClientTrait>)
+        .map(|client| {
+          let components = NodeComponents::from_full(&client);
+          (client as Box<dyn ClientTrait>, components)
+        })
         .map_err(|e| NodeError::Runtime(format!("Failed to start Full node: {}", e))),
       NodeType::Light => LightClient::start(conf, exit_sign)
-        .map(|client| client as Box<dyn ClientTrait>)
+        .map(|client| {
+          let components = NodeComponents::from_light(&client);
+          (client as Box<dyn ClientTrait>, components)
+        })
         .map_err(|e| NodeError::Runtime(format!("Failed to start Light node: {}", e))),
       NodeType::Unknown => Err(NodeError::Configuration("Unknown node type".to_string())),
     }
@@ -200,13 +836,11 @@ impl ConfluxNode {
     fs::create_dir_all(data_dir)
       .map_err(|e| NodeError::Initialization(format!("Failed to create data directory: {}", e)))?;
 
-    // set working directory， some file use relative path
-    env::set_current_dir(data_dir)
-      .map_err(|e| NodeError::Initialization(format!("Failed to set working directory: {}", e)))?;
-
     if let Some(ref log_conf) = config.log_conf {
-      log4rs::init_file(log_conf, Default::default())
-        .map_err(|e| NodeError::Configuration(format!("Failed to initialize logging: {}", e)))?;
+      globals::init_logging(
+        Path::new(&config::resolve_path(data_dir, log_conf)),
+        data_dir,
+      )?;
     };
 
     config
@@ -217,9 +851,14 @@ impl ConfluxNode {
   fn prepare_data_directory(
     &self,
     config: &config::ConfluxConfig,
-  ) -> Result<(std::path::PathBuf, Option<TempDir>)> {
+  ) -> Result<(PathBuf, Option<TempDir>)> {
     match config.conflux_data_dir.as_ref() {
-      Some(dir) => Ok((std::path::PathBuf::from(dir), None)),
+      Some(dir) => {
+        let path = std::path::absolute(dir).map_err(|e| {
+          NodeError::Initialization(format!("Failed to resolve data directory: {}", e))
+        })?;
+        Ok((path, None))
+      }
       None => {
         let temp_dir = tempdir().map_err(|e| {
           NodeError::Initialization(format!("Failed to create temp directory: {}", e))
@@ -230,3 +869,50 @@ impl ConfluxNode {
     }
   }
 }
+
+/// Take the lifecycle out of `lifecycle` and shut it down. Returns `None` if
+/// the node was not running.
+async fn stop_lifecycle(
+  lifecycle: &TokioMutex<Option<NodeLifecycle>>,
+  status: &StatusTracker,
+  timeout: Option<Duration>,
+) -> Result<Option<ShutdownReport>> {
+  let Some(lifecycle) = lifecycle.lock().await.take() else {
+    return Ok(None);
+  };
+
+  status.transition(NodeState::Stopping);
+  let (report, _) = lifecycle
+    .shutdown(timeout)
+    .await
+    .inspect_err(|e| status.fail(e))?;
+  status.transition(NodeState::Stopped);
+  info!("Node shutdown complete: {:?}", report);
+
+  Ok(Some(report))
+}
+
+/// A node whose JS object is garbage-collected while still running would keep
+/// its client thread and temp dir alive, so it is shut down here on a best
+/// effort basis.
+impl Drop for ConfluxNode {
+  fn drop(&mut self) {
+    self.cancel_start();
+
+    let lifecycle = match self.lifecycle.try_lock() {
+      Ok(mut lifecycle_guard) => lifecycle_guard.take(),
+      Err(_) => None,
+    };
+    if let Some(lifecycle) = lifecycle {
+      warn!("ConfluxNode dropped while running, shutting the node down");
+      // Drop runs on the JS thread during garbage collection, the shutdown
+      // must not hold it up.
+      let spawned = thread::Builder::new()
+        .name("xcfx-drop-shutdown".into())
+        .spawn(move || lifecycle.shutdown_blocking(DROP_SHUTDOWN_TIMEOUT));
+      if let Err(e) = spawned {
+        warn!("Failed to spawn the shutdown thread: {}", e);
+      }
+    }
+  }
+}
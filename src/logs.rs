@@ -0,0 +1,280 @@
+use crate::{
+  error::Result,
+  events::{epoch_blocks, ChainChange},
+  query::{hex, parse_address, parse_hash, to_u64, Space},
+  ConfluxNode,
+};
+use cfx_types::{Address, H256};
+use cfxcore::ConsensusGraph;
+use napi::{
+  bindgen_prelude::{AsyncGenerator, BigInt},
+  tokio::sync::{mpsc, Mutex as TokioMutex},
+};
+use napi_derive::napi;
+use primitives::log_entry::LogEntry as ReceiptLog;
+use rustc_hex::ToHex;
+use std::{collections::BTreeMap, future::Future, sync::Arc};
+
+// Logs of epochs further back can't be marked as removed anymore.
+const REMEMBERED_EPOCHS: u64 = 10_000;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct LogFilter {
+  /// Both spaces by default.
+  pub space: Option<Space>,
+  /// Logs of any of these contracts, all contracts by default.
+  pub address: Option<Vec<String>>,
+  /// Topic `i` of a log must be one of `topics[i]`, `null` matches any topic.
+  pub topics: Option<Vec<Option<Vec<String>>>>,
+  /// Start with the logs of this executed epoch, the next executed epoch by
+  /// default.
+  pub from_epoch: Option<BigInt>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct LogEntry {
+  pub address: String,
+  pub space: Space,
+  pub topics: Vec<String>,
+  pub data: String,
+  pub transaction_hash: String,
+  pub transaction_index: u32,
+  /// The index of the log within its transaction.
+  pub log_index: u32,
+  pub block_hash: String,
+  pub epoch_number: BigInt,
+  /// `true` if a pivot switch reverted the log, it was delivered before.
+  pub removed: bool,
+}
+
+/// An async iterator over the logs of one `logs` call. It ends when the node
+/// stops.
+#[napi(async_iterator)]
+pub struct LogStream {
+  receiver: Arc<TokioMutex<mpsc::UnboundedReceiver<LogEntry>>>,
+}
+
+#[napi]
+impl AsyncGenerator for LogStream {
+  type Yield = LogEntry;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl Future<Output = napi::Result<Option<Self::Yield>>> + Send + 'static {
+    let receiver = self.receiver.clone();
+    async move { Ok(receiver.lock().await.recv().await) }
+  }
+
+  /// Called by `return()`, for example when a `for await` loop is left.
+  fn complete(
+    &mut self,
+    _value: Option<Self::Return>,
+  ) -> impl Future<Output = napi::Result<Option<Self::Yield>>> + Send + 'static {
+    let receiver = self.receiver.clone();
+    async move {
+      receiver.lock().await.close();
+      Ok(None)
+    }
+  }
+}
+
+struct ParsedFilter {
+  space: Option<Space>,
+  addresses: Vec<Address>,
+  topics: Vec<Option<Vec<H256>>>,
+}
+
+impl ParsedFilter {
+  fn matches(&self, log: &ReceiptLog) -> bool {
+    let space = Space::from(log.space);
+    self.space.is_none_or(|s| s == space)
+      && (self.addresses.is_empty() || self.addresses.contains(&log.address))
+      && self
+        .topics
+        .iter()
+        .enumerate()
+        .all(|(i, topics)| match topics {
+          None => true,
+          Some(topics) => log.topics.get(i).is_some_and(|t| topics.contains(t)),
+        })
+  }
+}
+
+/// The receiving end of a `LogStream`, fed by the chain watcher.
+pub struct LogSubscription {
+  filter: ParsedFilter,
+  sender: mpsc::UnboundedSender<LogEntry>,
+  /// The first epoch not delivered yet, `None` until the first executed epoch
+  /// of a stream without `from_epoch`.
+  next_epoch: Option<u64>,
+  /// Logs delivered per epoch, to mark them removed after a pivot switch.
+  delivered: BTreeMap<u64, Vec<LogEntry>>,
+}
+
+impl LogSubscription {
+  /// Send the logs of `changes`, catching up on history before. `executed` is
+  /// the latest executed epoch after the changes.
+  ///
+  /// Returns `false` once the stream is gone.
+  pub fn deliver(
+    &mut self,
+    consensus: &ConsensusGraph,
+    changes: &[ChainChange],
+    executed: u64,
+  ) -> bool {
+    for change in changes {
+      match change {
+        ChainChange::Reverted(epoch) => {
+          for log in self.delivered.remove(epoch).unwrap_or_default() {
+            let _ = self.sender.send(LogEntry {
+              removed: true,
+              ..log
+            });
+          }
+          if let Some(next) = self.next_epoch.as_mut() {
+            *next = (*next).min(*epoch);
+          }
+        }
+        ChainChange::Executed(epoch, block_hashes) => {
+          let next = *self.next_epoch.get_or_insert(*epoch);
+          if *epoch < next {
+            continue;
+          }
+          self.catch_up(consensus, epoch.saturating_sub(1));
+          self.send_epoch(consensus, *epoch, block_hashes);
+          self.next_epoch = Some(epoch + 1);
+        }
+        ChainChange::Mined(..) => {}
+      }
+    }
+    self.catch_up(consensus, executed);
+
+    let last = self.next_epoch.unwrap_or_default();
+    self
+      .delivered
+      .retain(|&epoch, _| epoch + REMEMBERED_EPOCHS >= last);
+    !self.sender.is_closed()
+  }
+
+  /// Deliver the epochs up to `until` that were not delivered yet.
+  fn catch_up(&mut self, consensus: &ConsensusGraph, until: u64) {
+    let Some(next) = self.next_epoch else {
+      return;
+    };
+    for epoch in next..=until {
+      let Some(block_hashes) = epoch_blocks(consensus, epoch) else {
+        return;
+      };
+      self.send_epoch(consensus, epoch, &block_hashes);
+      self.next_epoch = Some(epoch + 1);
+    }
+  }
+
+  fn send_epoch(&mut self, consensus: &ConsensusGraph, epoch: u64, block_hashes: &[H256]) {
+    let logs = epoch_logs(consensus, epoch, block_hashes, &self.filter);
+    for log in &logs {
+      let _ = self.sender.send(log.clone());
+    }
+    if !logs.is_empty() {
+      self.delivered.insert(epoch, logs);
+    }
+  }
+}
+
+/// The logs of the executed `epoch` that match `filter`, in execution order.
+fn epoch_logs(
+  consensus: &ConsensusGraph,
+  epoch: u64,
+  block_hashes: &[H256],
+  filter: &ParsedFilter,
+) -> Vec<LogEntry> {
+  let data_man = consensus.data_manager();
+  let Some(pivot_hash) = block_hashes.last() else {
+    return Vec::new();
+  };
+
+  let mut logs = Vec::new();
+  for block_hash in block_hashes {
+    let (Some(block), Some(result)) = (
+      data_man.block_by_hash(block_hash, false),
+      data_man.block_execution_result_by_hash_with_epoch(block_hash, pivot_hash, false, false),
+    ) else {
+      continue;
+    };
+
+    let receipts = &result.block_receipts.receipts;
+    for (index, (tx, receipt)) in block.transactions.iter().zip(receipts).enumerate() {
+      for (log_index, log) in receipt.logs.iter().enumerate() {
+        if !filter.matches(log) {
+          continue;
+        }
+        logs.push(LogEntry {
+          address: hex(&log.address),
+          space: log.space.into(),
+          topics: log.topics.iter().map(hex).collect(),
+          data: format!("0x{}", log.data.to_hex::<String>()),
+          transaction_hash: hex(&tx.hash()),
+          transaction_index: index as u32,
+          log_index: log_index as u32,
+          block_hash: hex(block_hash),
+          epoch_number: BigInt::from(epoch),
+          removed: false,
+        });
+      }
+    }
+  }
+  logs
+}
+
+#[napi]
+impl ConfluxNode {
+  /// Stream the logs of executed transactions as they are executed, without
+  /// RPC filters that expire.
+  ///
+  /// Logs that a pivot switch reverts are delivered again with `removed` set,
+  /// their transactions' logs on the new pivot chain follow.
+  #[napi]
+  pub async fn logs(&self, filter: Option<LogFilter>) -> Result<LogStream> {
+    let watcher = self.chain_watcher().await?;
+    let filter = filter.unwrap_or_default();
+
+    let space = filter.space;
+    let addresses = filter
+      .address
+      .unwrap_or_default()
+      .iter()
+      .map(|address| parse_address(address, space.unwrap_or(Space::Native)).map(|a| a.address))
+      .collect::<Result<_>>()?;
+    let topics = filter
+      .topics
+      .unwrap_or_default()
+      .into_iter()
+      .map(|topics| {
+        topics
+          .map(|topics| topics.iter().map(|topic| parse_hash(topic)).collect())
+          .transpose()
+      })
+      .collect::<Result<_>>()?;
+    let next_epoch = filter.from_epoch.as_ref().map(to_u64).transpose()?;
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    watcher.subscribe_logs(LogSubscription {
+      filter: ParsedFilter {
+        space,
+        addresses,
+        topics,
+      },
+      sender,
+      next_epoch,
+      delivered: BTreeMap::new(),
+    });
+    Ok(LogStream {
+      receiver: Arc::new(TokioMutex::new(receiver)),
+    })
+  }
+}
@@ -0,0 +1,692 @@
+use crate::{
+  background::BackgroundThread,
+  clock::Clock,
+  components::NodeComponents,
+  error::{NodeError, Result},
+  query::{parse_hash, receipt_info, to_u64},
+  ConfluxNode,
+};
+use blockgen::BlockGenerator;
+use cfx_config::Configuration;
+use cfx_parameters::{block::MAX_BLOCK_SIZE_IN_BYTES, consensus::DEFERRED_STATE_EPOCH_COUNT};
+use cfx_types::{H256, U256};
+use cfxcore::{ConsensusGraph, TransactionPool};
+use log::warn;
+use napi::{bindgen_prelude::BigInt, tokio::task};
+use napi_derive::napi;
+use parking_lot::Mutex;
+use primitives::{EpochNumber, SignedTransaction, TransactionWithSignature};
+use rustc_hex::FromHex;
+use std::{
+  collections::HashSet,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+  },
+  thread,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_TXS_PER_BLOCK: u32 = 3000;
+const DEFAULT_MAX_BLOCKS_UNTIL_RECEIPT: u32 = 100;
+// How long to wait for a due epoch to be executed before mining on anyway.
+const EXECUTION_WAIT: Duration = Duration::from_secs(5);
+const EXECUTION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+// How often automine checks the pool counters for new transactions.
+const AUTOMINE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+// Upper bound for a branch that has to outweigh the current pivot chain.
+const MAX_FORK_BLOCKS: usize = 10_000;
+
+#[napi(object)]
+pub struct MinedBlock {
+  pub hash: String,
+  /// `null` if the block did not become part of an epoch yet.
+  pub epoch_number: Option<BigInt>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MiningMode {
+  /// Mine `DEFERRED_STATE_EPOCH_COUNT` blocks whenever new transactions
+  /// become ready to be packed, so they are executed right away. Transactions
+  /// waiting for a missing nonce don't trigger it until the gap is filled.
+  ///
+  /// `request` and `sendUnsignedTransaction` resolve once the transactions
+  /// they sent are packed, so does `cfx_sendRawTransaction` on the ports.
+  /// The ports answer that way as long as the node started with automine,
+  /// `setAutomine` can't change them. eSpace ports answer right away.
+  pub automine: bool,
+  /// Mine a block packing the pool every `interval_ms`.
+  pub interval_ms: Option<u32>,
+  /// Whether automatic block production is paused. Manual mining still works.
+  pub paused: bool,
+}
+
+#[napi(object)]
+pub struct CustomBlockOptions {
+  pub parent_hash: String,
+  /// Blocks referenced besides the parent, e.g. blocks of a competing branch.
+  pub referee_hashes: Option<Vec<String>>,
+  /// Unix timestamp in seconds, the node time by default.
+  pub timestamp: Option<BigInt>,
+  /// Raw signed transactions to pack, in both spaces. The pool is not used.
+  pub transactions: Option<Vec<String>>,
+}
+
+#[napi(object)]
+pub struct ReorgResult {
+  /// The pivot blocks that were replaced, oldest first.
+  pub removed_pivot_hashes: Vec<String>,
+  /// The blocks of the new, heavier branch, oldest first.
+  pub new_blocks: Vec<MinedBlock>,
+}
+
+/// Block production for dev nodes.
+///
+/// Blocks are generated synchronously, every method resolves once the new
+/// blocks are part of the tree-graph.
+#[napi]
+impl ConfluxNode {
+  /// How blocks are produced automatically right now.
+  #[napi]
+  pub async fn get_mining_mode(&self) -> Result<MiningMode> {
+    Ok(self.mining().await?.mode())
+  }
+
+  /// Turn mining on new pool transactions on or off.
+  #[napi]
+  pub async fn set_automine(&self, enabled: bool) -> Result<()> {
+    self
+      .update_mining(move |mode| mode.automine = enabled)
+      .await
+  }
+
+  /// Mine a block every `interval_ms`, `0` turns interval mining off.
+  #[napi]
+  pub async fn set_interval_mining(&self, interval_ms: u32) -> Result<()> {
+    self
+      .update_mining(move |mode| mode.interval_ms = Some(interval_ms).filter(|ms| *ms > 0))
+      .await
+  }
+
+  /// Stop automatic block production until `resume_mining` is called.
+  #[napi]
+  pub async fn pause_mining(&self) -> Result<()> {
+    self.update_mining(|mode| mode.paused = true).await
+  }
+
+  #[napi]
+  pub async fn resume_mining(&self) -> Result<()> {
+    self.update_mining(|mode| mode.paused = false).await
+  }
+
+  /// Generate `count` empty blocks.
+  #[napi]
+  pub async fn mine_blocks(&self, count: u32) -> Result<Vec<MinedBlock>> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    run_blocking(move || {
+      let producer = BlockProducer::new(&components, clock)?;
+      (0..count).map(|_| producer.mine(0)).collect()
+    })
+    .await
+  }
+
+  /// Generate `count` blocks that pack up to `max_txs_per_block` transactions
+  /// each from the pool.
+  #[napi]
+  pub async fn mine_pending_blocks(
+    &self,
+    count: u32,
+    max_txs_per_block: Option<u32>,
+  ) -> Result<Vec<MinedBlock>> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    let num_txs = max_txs_per_block.unwrap_or(DEFAULT_TXS_PER_BLOCK);
+    run_blocking(move || {
+      let producer = BlockProducer::new(&components, clock)?;
+      (0..count).map(|_| producer.mine(num_txs)).collect()
+    })
+    .await
+  }
+
+  /// Generate blocks that pack the pool until the transaction `tx_hash` has a
+  /// receipt, including the blocks needed for its epoch to be executed.
+  ///
+  /// Fails after `max_blocks` (default 100) blocks without a receipt.
+  #[napi]
+  pub async fn mine_until_receipt(
+    &self,
+    tx_hash: String,
+    max_blocks: Option<u32>,
+  ) -> Result<Vec<MinedBlock>> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    let tx_hash = parse_hash(&tx_hash)?;
+    let max_blocks = max_blocks.unwrap_or(DEFAULT_MAX_BLOCKS_UNTIL_RECEIPT);
+    run_blocking(move || {
+      let producer = BlockProducer::new(&components, clock)?;
+      producer.mine_until_receipt(&tx_hash, max_blocks)
+    })
+    .await
+  }
+}
+
+#[napi]
+impl ConfluxNode {
+  /// Generate one block with an explicit parent, referees, timestamp and
+  /// transactions. The block does not have to extend the pivot chain.
+  #[napi]
+  pub async fn mine_custom_block(&self, options: CustomBlockOptions) -> Result<MinedBlock> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    let parent = parse_hash(&options.parent_hash)?;
+    let referees = options
+      .referee_hashes
+      .unwrap_or_default()
+      .iter()
+      .map(|hash| parse_hash(hash))
+      .collect::<Result<Vec<_>>>()?;
+    let timestamp = options.timestamp.as_ref().map(to_u64).transpose()?;
+    let transactions = options
+      .transactions
+      .unwrap_or_default()
+      .iter()
+      .map(|raw| decode_raw_transaction(raw))
+      .collect::<Result<Vec<_>>>()?;
+
+    run_blocking(move || {
+      let producer = BlockProducer::new(&components, clock)?;
+      producer.mine_custom(parent, referees, timestamp, transactions)
+    })
+    .await
+  }
+
+  /// Replace the last `depth` pivot blocks with a competing branch of at least
+  /// `depth + 1` empty blocks forked from the pivot block before them. The
+  /// branch is extended until it is the pivot chain.
+  ///
+  /// Automatic mining should be paused, blocks mined on the old branch while
+  /// the reorg runs can outweigh the new one.
+  #[napi]
+  pub async fn reorg(&self, depth: u32) -> Result<ReorgResult> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    run_blocking(move || {
+      let producer = BlockProducer::new(&components, clock)?;
+      producer.reorg(depth as u64)
+    })
+    .await
+  }
+}
+
+pub struct BlockProducer {
+  blockgen: Arc<BlockGenerator>,
+  consensus: Arc<ConsensusGraph>,
+  components: NodeComponents,
+  clock: Arc<Clock>,
+}
+
+impl BlockProducer {
+  pub fn new(components: &NodeComponents, clock: Arc<Clock>) -> Result<Self> {
+    Ok(BlockProducer {
+      blockgen: components.blockgen()?,
+      consensus: components.consensus()?,
+      components: components.clone(),
+      clock,
+    })
+  }
+
+  /// Generate one block for automatic block production, which has nobody to
+  /// report a failure to.
+  fn mine_logged(&self) {
+    if let Err(e) = self.mine(DEFAULT_TXS_PER_BLOCK) {
+      warn!("Automatic block production failed: {}", e);
+    }
+  }
+
+  /// Generate one block on the current pivot chain, packing up to `num_txs`
+  /// transactions from the pool.
+  pub fn mine(&self, num_txs: u32) -> Result<MinedBlock> {
+    // Let the generator pick parent, referees and transactions as usual, then
+    // generate the block again without the abandoned branches and with the
+    // node time.
+    let assembled =
+      self
+        .blockgen
+        .assemble_new_block(num_txs as usize, MAX_BLOCK_SIZE_IN_BYTES, vec![]);
+    let header = &assembled.block_header;
+    let referees = header
+      .referee_hashes()
+      .iter()
+      .filter(|hash| !self.components.is_abandoned(hash))
+      .copied()
+      .collect();
+    self.mine_custom(
+      *header.parent_hash(),
+      referees,
+      None,
+      assembled.transactions.clone(),
+    )
+  }
+
+  pub fn mine_custom(
+    &self,
+    parent: H256,
+    referees: Vec<H256>,
+    timestamp: Option<u64>,
+    transactions: Vec<Arc<SignedTransaction>>,
+  ) -> Result<MinedBlock> {
+    self
+      .generate(parent, referees, timestamp, transactions)
+      .map(|hash| self.mined_block(hash))
+  }
+
+  /// Generate a block with exactly the given parent, referees and
+  /// transactions, timestamped with the node time unless `timestamp` is set.
+  pub fn generate(
+    &self,
+    parent: H256,
+    referees: Vec<H256>,
+    timestamp: Option<u64>,
+    transactions: Vec<Arc<SignedTransaction>>,
+  ) -> Result<H256> {
+    let generated = match timestamp.or_else(|| self.clock.next_timestamp()) {
+      Some(timestamp) => self.blockgen.generate_block_with_nonce_and_timestamp(
+        parent,
+        referees,
+        transactions,
+        next_nonce(),
+        timestamp,
+        false,
+      ),
+      None => {
+        self
+          .blockgen
+          .generate_custom_block_with_parent(parent, referees, transactions, false, None)
+      }
+    };
+    generated.map_err(|e| NodeError::Runtime(format!("Failed to generate block: {}", e)))
+  }
+
+  fn reorg(&self, depth: u64) -> Result<ReorgResult> {
+    let best_epoch = self.consensus.best_epoch_number();
+    let fork_epoch = best_epoch.checked_sub(depth).ok_or_else(|| {
+      NodeError::Runtime(format!(
+        "Can't reorg {} epochs, the best epoch is {}",
+        depth, best_epoch
+      ))
+    })?;
+
+    let pivot_hash = |epoch| {
+      self
+        .consensus
+        .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+        .map_err(|e| NodeError::Runtime(format!("Epoch {} is not available: {}", epoch, e)))
+    };
+    let removed_pivot_hashes = (fork_epoch + 1..=best_epoch)
+      .map(|epoch| pivot_hash(epoch).map(|hash| format!("{:#x}", hash)))
+      .collect::<Result<Vec<_>>>()?;
+
+    let mut parent = pivot_hash(fork_epoch)?;
+    let mut new_blocks = Vec::new();
+    for _ in 0..=depth {
+      let block = self.mine_custom(parent, vec![], None, vec![])?;
+      parent = parse_hash(&block.hash)?;
+      new_blocks.push(block);
+    }
+    // Blocks referencing the old branch can still outweigh the new one.
+    new_blocks.extend(self.fork_to_pivot(parent)?);
+
+    // Epoch numbers of the new branch are only known once it became the pivot.
+    let new_blocks = new_blocks
+      .into_iter()
+      .map(|block| parse_hash(&block.hash).map(|hash| self.mined_block(hash)))
+      .collect::<Result<Vec<_>>>()?;
+
+    Ok(ReorgResult {
+      removed_pivot_hashes,
+      new_blocks,
+    })
+  }
+
+  /// Extend a new branch of empty blocks from `parent` until it is the pivot
+  /// chain, then wait for its state to be executed.
+  pub fn fork_to_pivot(&self, parent: H256) -> Result<Vec<MinedBlock>> {
+    let mut tip = parent;
+    let mut mined = Vec::new();
+
+    while self.consensus.best_block_hash() != tip {
+      if mined.len() >= MAX_FORK_BLOCKS {
+        return Err(NodeError::Timeout(format!(
+          "The branch from {:#x} is not the pivot chain after {} blocks",
+          parent, MAX_FORK_BLOCKS
+        )));
+      }
+      let block = self.mine_custom(tip, vec![], None, vec![])?;
+      tip = parse_hash(&block.hash)?;
+      mined.push(block);
+    }
+    self.wait_for_execution();
+
+    Ok(mined)
+  }
+
+  /// Keep every branch but the pivot chain out of the blocks mined from now
+  /// on.
+  pub fn abandon_other_branches(&self) {
+    let best = self.consensus.best_block_hash();
+    let terminals = self
+      .consensus
+      .best_info()
+      .bounded_terminal_block_hashes
+      .clone();
+    self
+      .components
+      .abandon(terminals.into_iter().filter(|hash| *hash != best));
+  }
+
+  pub fn mined_block(&self, hash: H256) -> MinedBlock {
+    MinedBlock {
+      hash: format!("{:#x}", hash),
+      epoch_number: self
+        .consensus
+        .get_block_epoch_number(&hash)
+        .map(BigInt::from),
+    }
+  }
+
+  fn mine_until_receipt(&self, tx_hash: &H256, max_blocks: u32) -> Result<Vec<MinedBlock>> {
+    let mut mined = Vec::new();
+
+    while receipt_info(&self.consensus, tx_hash).is_none() {
+      if mined.len() as u32 >= max_blocks {
+        return Err(NodeError::Timeout(format!(
+          "Transaction {:#x} has no receipt after {} blocks",
+          tx_hash, max_blocks
+        )));
+      }
+      mined.push(self.mine(DEFAULT_TXS_PER_BLOCK)?);
+      self.wait_for_execution();
+    }
+
+    Ok(mined)
+  }
+
+  /// Mine the blocks that pack the pool and execute the packed transactions,
+  /// like automine does.
+  pub fn mine_and_execute(&self) -> Result<Vec<MinedBlock>> {
+    let mined = (0..DEFERRED_STATE_EPOCH_COUNT)
+      .map(|_| self.mine(DEFAULT_TXS_PER_BLOCK))
+      .collect::<Result<Vec<_>>>()?;
+    self.wait_for_execution();
+    Ok(mined)
+  }
+
+  /// Mine `count` empty blocks on the pivot chain.
+  pub fn mine_empty(&self, count: u64) -> Result<()> {
+    for _ in 0..count {
+      self.mine(0)?;
+    }
+    Ok(())
+  }
+
+  pub fn consensus(&self) -> &ConsensusGraph {
+    &self.consensus
+  }
+
+  /// Execution runs behind block generation, so wait until the epoch that the
+  /// latest block made due is executed before checking for receipts.
+  fn wait_for_execution(&self) {
+    let Some(epoch) = self
+      .consensus
+      .best_epoch_number()
+      .checked_sub(DEFERRED_STATE_EPOCH_COUNT)
+    else {
+      return;
+    };
+    let Ok(epoch_hash) = self
+      .consensus
+      .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+    else {
+      return;
+    };
+
+    self.wait_until_executed(&epoch_hash);
+  }
+
+  /// Wait for the epoch of pivot block `epoch_hash` to be executed, `false` if
+  /// it wasn't in time.
+  pub fn wait_until_executed(&self, epoch_hash: &H256) -> bool {
+    let data_man = self.consensus.data_manager();
+    let deadline = Instant::now() + EXECUTION_WAIT;
+    while !data_man.epoch_executed(epoch_hash) {
+      if Instant::now() >= deadline {
+        return false;
+      }
+      thread::sleep(EXECUTION_POLL_INTERVAL);
+    }
+    true
+  }
+}
+
+/// Drives automatic block production of a dev node.
+///
+/// The client is started without `dev_block_interval_ms`, which is fixed once
+/// it runs, so this controller mines on an interval instead and the interval
+/// can change without a restart. `dev_pack_tx_immediately` is left to the
+/// client: its core space RPC packs sent transactions before answering, as
+/// long as the node started with automine.
+pub struct MiningController {
+  mode: Mutex<MiningMode>,
+  /// Whether the client's core space RPC packs sent transactions itself.
+  packs_on_send: bool,
+  /// Held while blocks are produced on the background thread.
+  producing: Mutex<()>,
+  /// The ready transactions automine mined for last.
+  mined_for: Mutex<HashSet<H256>>,
+  components: NodeComponents,
+  clock: Arc<Clock>,
+  thread: BackgroundThread,
+}
+
+impl MiningController {
+  /// Take the block production settings out of a dev mode configuration.
+  pub fn take_mode(conf: &mut Configuration) -> Option<MiningMode> {
+    if !conf.is_dev_mode() {
+      return None;
+    }
+
+    let raw_conf = &mut conf.raw_conf;
+    let interval_ms = raw_conf.dev_block_interval_ms.take();
+    let automine = raw_conf
+      .dev_pack_tx_immediately
+      .unwrap_or(interval_ms.is_none());
+    raw_conf.dev_pack_tx_immediately = Some(automine);
+
+    Some(MiningMode {
+      automine,
+      interval_ms: interval_ms.map(|ms| ms.min(u32::MAX as u64) as u32),
+      paused: false,
+    })
+  }
+
+  /// Start producing blocks in `mode` on a background thread. The thread ends
+  /// once the controller is stopped or the node is gone.
+  pub fn start(mode: MiningMode, components: NodeComponents, clock: Arc<Clock>) -> Arc<Self> {
+    let controller = Arc::new(MiningController {
+      mode: Mutex::new(mode),
+      packs_on_send: mode.automine,
+      producing: Mutex::new(()),
+      mined_for: Mutex::new(HashSet::new()),
+      components,
+      clock,
+      thread: BackgroundThread::default(),
+    });
+
+    let runner = controller.clone();
+    controller.thread.spawn("xcfx-miner", move || runner.run());
+    controller
+  }
+
+  pub fn mode(&self) -> MiningMode {
+    *self.mode.lock()
+  }
+
+  /// Change the mode. Blocks in progress are finished before it returns, none
+  /// are produced in the old mode afterwards.
+  pub fn update(&self, f: impl FnOnce(&mut MiningMode)) {
+    f(&mut self.mode.lock());
+    self.thread.wake();
+    drop(self.producing.lock());
+  }
+
+  /// Stop block production and wait for a block in progress, so the client
+  /// is the sole owner of its services again.
+  pub fn stop(&self) {
+    self.thread.stop();
+  }
+
+  /// Automine for transactions a method of the node just sent, so the method
+  /// resolves once they are packed.
+  pub fn mine_sent(&self) -> Result<()> {
+    let mode = self.mode();
+    if !mode.automine || mode.paused {
+      return Ok(());
+    }
+    let producer = BlockProducer::new(&self.components, self.clock.clone())?;
+    self.mine_new_ready(&producer, &self.components.txpool()?, true);
+    Ok(())
+  }
+
+  fn run(&self) {
+    let mut last_interval_block = Instant::now();
+    let mut last_stats = None;
+
+    loop {
+      let mode = self.mode();
+      let timeout = match mode.interval_ms {
+        _ if mode.paused => None,
+        Some(interval_ms) => {
+          let due = last_interval_block + Duration::from_millis(interval_ms as u64);
+          let wait = due.saturating_duration_since(Instant::now());
+          Some(match mode.automine {
+            true => wait.min(AUTOMINE_POLL_INTERVAL),
+            false => wait,
+          })
+        }
+        None if mode.automine => Some(AUTOMINE_POLL_INTERVAL),
+        None => None,
+      };
+      if !self.thread.wait(timeout) {
+        return;
+      }
+      // Re-evaluate after any change before producing a block. `update`
+      // changes the mode before it waits for the lock.
+      let _producing = self.producing.lock();
+      if mode.paused || self.mode() != mode {
+        continue;
+      }
+
+      let (producer, txpool) = match (
+        BlockProducer::new(&self.components, self.clock.clone()),
+        self.components.txpool(),
+      ) {
+        (Ok(producer), Ok(txpool)) => (producer, txpool),
+        _ => {
+          warn!("The node is gone, stopping block production");
+          return;
+        }
+      };
+
+      if let Some(interval_ms) = mode.interval_ms {
+        if last_interval_block.elapsed() >= Duration::from_millis(interval_ms as u64) {
+          producer.mine_logged();
+          last_interval_block = Instant::now();
+        }
+      }
+      // The pool content is only read once its counters moved. Core space
+      // transactions sent to the ports are packed by the client already.
+      let stats = txpool.stats();
+      if mode.automine && last_stats != Some(stats) {
+        last_stats = Some(stats);
+        self.mine_new_ready(&producer, &txpool, !self.packs_on_send);
+      }
+    }
+  }
+
+  /// Mine `DEFERRED_STATE_EPOCH_COUNT` blocks if transactions became ready
+  /// since the last check. Only the ready ones are remembered, a transaction
+  /// that stays unpacked must not make automine produce empty blocks over and
+  /// over.
+  fn mine_new_ready(&self, producer: &BlockProducer, txpool: &TransactionPool, core_space: bool) {
+    let mut mined_for = self.mined_for.lock();
+    let (ready, _) = txpool.content(None);
+    let ready: HashSet<H256> = ready
+      .iter()
+      .filter(|tx| core_space || tx.space() == cfx_types::Space::Ethereum)
+      .map(|tx| tx.hash())
+      .collect();
+    let new = ready.iter().any(|hash| !mined_for.contains(hash));
+    *mined_for = ready;
+
+    if new {
+      for _ in 0..DEFERRED_STATE_EPOCH_COUNT {
+        producer.mine_logged();
+      }
+    }
+  }
+}
+
+impl ConfluxNode {
+  /// Resolves once blocks of the previous mode are done.
+  async fn update_mining(&self, f: impl FnOnce(&mut MiningMode) + Send + 'static) -> Result<()> {
+    let mining = self.mining().await?;
+    run_blocking(move || {
+      mining.update(f);
+      Ok(())
+    })
+    .await
+  }
+
+  /// Let automine pack what a method of the node just sent. Nodes without
+  /// block production control have nothing to do.
+  pub(crate) async fn automine_sent(&self) -> Result<()> {
+    let Ok(mining) = self.mining().await else {
+      return Ok(());
+    };
+    run_blocking(move || mining.mine_sent()).await
+  }
+}
+
+/// Decode a raw signed transaction and recover its sender.
+pub fn decode_raw_transaction(raw: &str) -> Result<Arc<SignedTransaction>> {
+  let invalid = |e: String| NodeError::Runtime(format!("Invalid raw transaction: {}", e));
+
+  let bytes: Vec<u8> = raw
+    .trim_start_matches("0x")
+    .from_hex()
+    .map_err(|e| invalid(e.to_string()))?;
+  let tx = TransactionWithSignature::from_raw(&bytes).map_err(|e| invalid(e.to_string()))?;
+  let public = tx.recover_public().map_err(|e| invalid(e.to_string()))?;
+  Ok(Arc::new(SignedTransaction::new(public, tx)))
+}
+
+/// Blocks with the same parent, referees and timestamp still need distinct
+/// hashes, also across restarts of the node.
+fn next_nonce() -> U256 {
+  static COUNTER: AtomicU64 = AtomicU64::new(0);
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos() as u64)
+    .unwrap_or_default();
+  U256::from(nanos) + U256::from(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+pub async fn run_blocking<T: Send + 'static>(
+  f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+  task::spawn_blocking(f)
+    .await
+    .map_err(|e| NodeError::Runtime(format!("Block production failed: {}", e)))?
+}
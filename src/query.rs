@@ -0,0 +1,360 @@
+use crate::{
+  error::{NodeError, Result},
+  impersonation::sender,
+  ConfluxNode,
+};
+use cfx_addr::cfx_addr_decode;
+use cfx_types::{Address, AddressSpaceUtil, AddressWithSpace, H256, U256};
+use cfxcore::ConsensusGraph;
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use primitives::{
+  receipt::TransactionStatus, Account, Action, Block, EpochNumber, SignedTransaction,
+};
+use rustc_hex::ToHex;
+use std::{str::FromStr, sync::Arc};
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Space {
+  /// The core space, addresses may be given in base32 or hex.
+  Native,
+  /// The eSpace, addresses are hex.
+  Evm,
+}
+
+#[napi(object)]
+pub struct AccountInfo {
+  pub balance: BigInt,
+  pub nonce: BigInt,
+}
+
+#[napi(object)]
+pub struct BlockInfo {
+  pub hash: String,
+  pub parent_hash: String,
+  pub height: BigInt,
+  /// The epoch the block was executed in, `null` if it is not in an epoch yet.
+  pub epoch_number: Option<BigInt>,
+  pub timestamp: BigInt,
+  pub miner: String,
+  pub difficulty: BigInt,
+  pub gas_limit: BigInt,
+  pub referee_hashes: Vec<String>,
+  pub transactions: Vec<String>,
+}
+
+#[napi(object)]
+pub struct TransactionInfo {
+  pub hash: String,
+  pub space: Space,
+  pub from: String,
+  /// `null` for contract creations.
+  pub to: Option<String>,
+  pub nonce: BigInt,
+  pub value: BigInt,
+  pub gas: BigInt,
+  pub gas_price: BigInt,
+  pub data: String,
+  /// Location of the transaction, all `null` while it is only in the pool.
+  pub block_hash: Option<String>,
+  pub index: Option<u32>,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutcomeStatus {
+  Success,
+  Failure,
+  Skipped,
+}
+
+#[napi(object)]
+pub struct LogInfo {
+  pub address: String,
+  pub space: Space,
+  pub topics: Vec<String>,
+  pub data: String,
+}
+
+#[napi(object)]
+pub struct ReceiptInfo {
+  pub transaction_hash: String,
+  pub block_hash: String,
+  pub epoch_number: BigInt,
+  pub index: u32,
+  pub outcome_status: OutcomeStatus,
+  pub gas_used: BigInt,
+  pub gas_fee: BigInt,
+  pub logs: Vec<LogInfo>,
+}
+
+/// Typed reads straight from consensus and storage.
+///
+/// Hashes, addresses and data are `0x` prefixed hex strings, quantities are
+/// BigInts. Lookups of things that don't exist resolve to `null`.
+#[napi]
+impl ConfluxNode {
+  #[napi]
+  pub async fn get_best_epoch_number(&self) -> Result<BigInt> {
+    let consensus = self.components().await?.consensus()?;
+    Ok(BigInt::from(consensus.best_epoch_number()))
+  }
+
+  #[napi]
+  pub async fn get_block_by_hash(&self, hash: String) -> Result<Option<BlockInfo>> {
+    let consensus = self.components().await?.consensus()?;
+    Ok(block_info(&consensus, &parse_hash(&hash)?))
+  }
+
+  /// The pivot block of `epoch`.
+  #[napi]
+  pub async fn get_block_by_epoch(&self, epoch: BigInt) -> Result<Option<BlockInfo>> {
+    let consensus = self.components().await?.consensus()?;
+    let Ok(hash) = consensus.get_hash_from_epoch_number(EpochNumber::Number(to_u64(&epoch)?))
+    else {
+      return Ok(None);
+    };
+    Ok(block_info(&consensus, &hash))
+  }
+
+  /// Pivot block hashes from `from_epoch` (default genesis) to `to_epoch`
+  /// (default the best epoch), both inclusive.
+  #[napi]
+  pub async fn get_pivot_chain(
+    &self,
+    from_epoch: Option<BigInt>,
+    to_epoch: Option<BigInt>,
+  ) -> Result<Vec<String>> {
+    let consensus = self.components().await?.consensus()?;
+    let from = from_epoch.as_ref().map(to_u64).transpose()?.unwrap_or(0);
+    let to = match to_epoch.as_ref() {
+      Some(epoch) => to_u64(epoch)?,
+      None => consensus.best_epoch_number(),
+    };
+
+    (from..=to)
+      .map(|epoch| {
+        consensus
+          .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+          .map(|hash| hex(&hash))
+          .map_err(|e| NodeError::Runtime(format!("Epoch {} is not available: {}", epoch, e)))
+      })
+      .collect()
+  }
+
+  /// A packed transaction, or one that is still waiting in the pool.
+  #[napi]
+  pub async fn get_transaction(&self, hash: String) -> Result<Option<TransactionInfo>> {
+    let components = self.components().await?;
+    let consensus = components.consensus()?;
+    let hash = parse_hash(&hash)?;
+
+    if let Some((block, index)) = packed_transaction(&consensus, &hash) {
+      let tx = &block.transactions[index];
+      return Ok(Some(transaction_info(tx, Some((&block.hash(), index)))));
+    }
+    let pooled = components
+      .txpool()
+      .ok()
+      .and_then(|txpool| txpool.get_transaction(&hash));
+    Ok(pooled.map(|tx| transaction_info(&tx, None)))
+  }
+
+  /// The receipt of an executed transaction.
+  #[napi]
+  pub async fn get_receipt(&self, hash: String) -> Result<Option<ReceiptInfo>> {
+    let consensus = self.components().await?.consensus()?;
+    Ok(receipt_info(&consensus, &parse_hash(&hash)?))
+  }
+
+  /// Balance and nonce of an account in the state of `epoch`, the latest
+  /// executed state by default.
+  #[napi]
+  pub async fn get_account(
+    &self,
+    address: String,
+    space: Space,
+    epoch: Option<BigInt>,
+  ) -> Result<AccountInfo> {
+    let consensus = self.components().await?.consensus()?;
+    let epoch = match epoch.as_ref() {
+      Some(epoch) => EpochNumber::Number(to_u64(epoch)?),
+      None => EpochNumber::LatestState,
+    };
+    let address = parse_address(&address, space)?;
+    let account = account_state(&consensus, &address, epoch)?;
+
+    Ok(AccountInfo {
+      balance: to_bigint(&account.as_ref().map(|a| a.balance).unwrap_or_default()),
+      nonce: to_bigint(&account.as_ref().map(|a| a.nonce).unwrap_or_default()),
+    })
+  }
+}
+
+/// The account at `address` in the state of `epoch`, `None` if it doesn't exist.
+pub fn account_state(
+  consensus: &ConsensusGraph,
+  address: &AddressWithSpace,
+  epoch: EpochNumber,
+) -> Result<Option<Account>> {
+  let state_db = consensus
+    .get_state_db_by_epoch_number(epoch, "epoch")
+    .map_err(|e| NodeError::Runtime(format!("State is not available: {}", e)))?;
+  state_db
+    .get_account(address)
+    .map_err(|e| NodeError::Runtime(format!("Failed to read account: {}", e)))
+}
+
+/// The block containing the executed transaction `hash` and its index.
+fn packed_transaction(consensus: &ConsensusGraph, hash: &H256) -> Option<(Arc<Block>, usize)> {
+  let data_man = consensus.data_manager();
+  let tx_index = data_man.transaction_index_by_hash(hash, false)?;
+  let block = data_man.block_by_hash(&tx_index.block_hash, false)?;
+  Some((block, tx_index.real_index))
+}
+
+fn block_info(consensus: &ConsensusGraph, hash: &H256) -> Option<BlockInfo> {
+  let block = consensus.data_manager().block_by_hash(hash, false)?;
+  let header = &block.block_header;
+  Some(BlockInfo {
+    hash: hex(hash),
+    parent_hash: hex(header.parent_hash()),
+    height: BigInt::from(header.height()),
+    epoch_number: consensus.get_block_epoch_number(hash).map(BigInt::from),
+    timestamp: BigInt::from(header.timestamp()),
+    miner: hex(header.author()),
+    difficulty: to_bigint(header.difficulty()),
+    gas_limit: to_bigint(header.gas_limit()),
+    referee_hashes: header.referee_hashes().iter().map(hex).collect(),
+    transactions: block
+      .transactions
+      .iter()
+      .map(|tx| hex(&tx.hash()))
+      .collect(),
+  })
+}
+
+pub fn transaction_info(
+  tx: &SignedTransaction,
+  location: Option<(&H256, usize)>,
+) -> TransactionInfo {
+  TransactionInfo {
+    hash: hex(&tx.hash()),
+    space: tx.space().into(),
+    from: hex(&sender(tx).address),
+    to: match tx.action() {
+      Action::Call(to) => Some(hex(&to)),
+      Action::Create => None,
+    },
+    nonce: to_bigint(tx.nonce()),
+    value: to_bigint(tx.value()),
+    gas: to_bigint(tx.gas()),
+    gas_price: to_bigint(tx.gas_price()),
+    data: format!("0x{}", tx.data().to_hex::<String>()),
+    block_hash: location.map(|(hash, _)| hex(hash)),
+    index: location.map(|(_, index)| index as u32),
+  }
+}
+
+pub fn receipt_info(consensus: &ConsensusGraph, hash: &H256) -> Option<ReceiptInfo> {
+  let (block, index) = packed_transaction(consensus, hash)?;
+  let block_hash = block.hash();
+  let epoch_number = consensus.get_block_epoch_number(&block_hash)?;
+  let pivot_hash = consensus
+    .get_hash_from_epoch_number(EpochNumber::Number(epoch_number))
+    .ok()?;
+  let result = consensus
+    .data_manager()
+    .block_execution_result_by_hash_with_epoch(&block_hash, &pivot_hash, false, false)?;
+
+  let receipts = &result.block_receipts.receipts;
+  let receipt = receipts.get(index)?;
+  let prior_gas_used = match index {
+    0 => U256::zero(),
+    _ => receipts[index - 1].accumulated_gas_used,
+  };
+
+  Some(ReceiptInfo {
+    transaction_hash: hex(hash),
+    block_hash: hex(&block_hash),
+    epoch_number: BigInt::from(epoch_number),
+    index: index as u32,
+    outcome_status: match receipt.outcome_status {
+      TransactionStatus::Success => OutcomeStatus::Success,
+      TransactionStatus::Failure => OutcomeStatus::Failure,
+      TransactionStatus::Skipped => OutcomeStatus::Skipped,
+    },
+    gas_used: to_bigint(&(receipt.accumulated_gas_used - prior_gas_used)),
+    gas_fee: to_bigint(&receipt.gas_fee),
+    logs: receipt
+      .logs
+      .iter()
+      .map(|log| LogInfo {
+        address: hex(&log.address),
+        space: log.space.into(),
+        topics: log.topics.iter().map(hex).collect(),
+        data: format!("0x{}", log.data.to_hex::<String>()),
+      })
+      .collect(),
+  })
+}
+
+impl From<cfx_types::Space> for Space {
+  fn from(space: cfx_types::Space) -> Self {
+    match space {
+      cfx_types::Space::Native => Space::Native,
+      cfx_types::Space::Ethereum => Space::Evm,
+    }
+  }
+}
+
+pub fn to_bigint(value: &U256) -> BigInt {
+  BigInt {
+    sign_bit: false,
+    words: value.0.to_vec(),
+  }
+}
+
+pub fn to_u256(value: &BigInt) -> Result<U256> {
+  if value.sign_bit || value.words.len() > 4 {
+    return Err(NodeError::Runtime(
+      "Expected a BigInt between 0 and 2^256 - 1".to_string(),
+    ));
+  }
+  let mut words = [0u64; 4];
+  words[..value.words.len()].copy_from_slice(&value.words);
+  Ok(U256(words))
+}
+
+pub fn to_u64(value: &BigInt) -> Result<u64> {
+  match value.get_u64() {
+    (false, value, true) => Ok(value),
+    _ => Err(NodeError::Runtime(
+      "Expected a BigInt between 0 and 2^64 - 1".to_string(),
+    )),
+  }
+}
+
+pub fn hex(value: &impl std::fmt::LowerHex) -> String {
+  format!("{:#x}", value)
+}
+
+pub fn parse_hash(hash: &str) -> Result<H256> {
+  H256::from_str(hash.trim_start_matches("0x"))
+    .map_err(|_| NodeError::Runtime(format!("{} is not a valid hash", hash)))
+}
+
+/// Parse a hex address, or a base32 address in the core space.
+pub fn parse_address(address: &str, space: Space) -> Result<AddressWithSpace> {
+  let hex_address = Address::from_str(address.trim_start_matches("0x")).ok();
+  match space {
+    Space::Evm => hex_address
+      .map(|a| a.with_evm_space())
+      .ok_or_else(|| NodeError::Runtime(format!("{} is not a valid address", address))),
+    Space::Native => hex_address
+      .or_else(|| cfx_addr_decode(address).ok().and_then(|d| d.hex_address))
+      .map(|a| a.with_native_space())
+      .ok_or_else(|| NodeError::Runtime(format!("invalid base32 address {}", address))),
+  }
+}
@@ -0,0 +1,81 @@
+use crate::{
+  components::NodeComponents,
+  error::{NodeError, Result},
+};
+use cfx_config::Configuration;
+use napi::tokio::{net::TcpStream, time};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The JSON-RPC ports the node was configured to listen on.
+pub fn rpc_ports(conf: &Configuration) -> Vec<u16> {
+  let raw_conf = &conf.raw_conf;
+  [
+    raw_conf.jsonrpc_http_port,
+    raw_conf.jsonrpc_ws_port,
+    raw_conf.jsonrpc_tcp_port,
+    raw_conf.jsonrpc_http_eth_port,
+    raw_conf.jsonrpc_ws_eth_port,
+    raw_conf.jsonrpc_local_http_port,
+    raw_conf.jsonrpc_local_ws_port,
+    raw_conf.jsonrpc_local_tcp_port,
+  ]
+  .into_iter()
+  .flatten()
+  .collect()
+}
+
+/// Wait until the node can serve requests: genesis is executed, consensus is
+/// in `NormalSyncPhase` and every configured RPC port accepts connections.
+pub async fn wait_until_ready(
+  components: &NodeComponents,
+  rpc_ports: &[u16],
+  timeout: Duration,
+) -> Result<()> {
+  let deadline = Instant::now() + timeout;
+
+  loop {
+    let Some(pending) = pending_condition(components, rpc_ports).await? else {
+      return Ok(());
+    };
+
+    if Instant::now() >= deadline {
+      return Err(NodeError::Timeout(format!(
+        "Node is not ready after {}ms: {}",
+        timeout.as_millis(),
+        pending
+      )));
+    }
+
+    time::sleep(POLL_INTERVAL).await;
+  }
+}
+
+/// Describes the first readiness condition that is not met yet.
+async fn pending_condition(
+  components: &NodeComponents,
+  rpc_ports: &[u16],
+) -> Result<Option<String>> {
+  let consensus = components.consensus()?;
+  let data_man = consensus.data_manager();
+  if !data_man.epoch_executed(&data_man.true_genesis.hash()) {
+    return Ok(Some("genesis is not executed".to_string()));
+  }
+
+  // Light nodes have no synchronization service to ask.
+  if let Ok(sync) = components.sync() {
+    let phase = sync.current_sync_phase().name();
+    if phase != "NormalSyncPhase" {
+      return Ok(Some(format!("sync phase is {}", phase)));
+    }
+  }
+
+  for port in rpc_ports {
+    if TcpStream::connect(("127.0.0.1", *port)).await.is_err() {
+      return Ok(Some(format!("RPC port {} is not bound", port)));
+    }
+  }
+
+  Ok(None)
+}
@@ -0,0 +1,342 @@
+use crate::{
+  anvil::DevContext,
+  cheats::{modify_account, parse_bytes, parse_word, set_code, set_storage},
+  components::NodeComponents,
+  error::{NodeError, Result},
+  events::ChainListeners,
+  mining::{decode_raw_transaction, BlockProducer},
+  query::{parse_address, Space},
+};
+use cfx_config::{ApiSet, Configuration};
+use cfx_rpc_builder::{
+  RpcModuleBuilder, RpcModuleSelection, TransportRpcModuleConfig, TransportRpcModules,
+};
+use cfx_rpc_cfx_types::RpcImplConfiguration;
+use cfx_tasks::TaskManager;
+use cfx_types::{AddressWithSpace, U256};
+use jsonrpsee::{
+  core::DeserializeOwned,
+  types::{
+    error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+    ErrorObjectOwned, Params,
+  },
+  RpcModule,
+};
+use napi::tokio::runtime::Handle;
+use napi_derive::napi;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::sync::{
+  atomic::{AtomicU64, Ordering},
+  Arc,
+};
+
+pub type RpcResult<T> = std::result::Result<T, ErrorObjectOwned>;
+
+#[napi(object)]
+pub struct RpcCall {
+  pub method: String,
+  pub params: Option<Value>,
+}
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct RpcError {
+  pub code: i32,
+  pub message: String,
+  pub data: Option<Value>,
+}
+
+/// Either `result` or `error` is set, like in a JSON-RPC response.
+#[napi(object)]
+pub struct RpcResponse {
+  pub result: Option<Value>,
+  pub error: Option<RpcError>,
+}
+
+/// The parts of the node configuration the in-process RPC needs, taken before
+/// the configuration is handed to the client.
+pub struct RpcSettings {
+  rpc_impl_config: RpcImplConfiguration,
+  core_apis: ApiSet,
+  evm_apis: RpcModuleSelection,
+  dev_mode: bool,
+}
+
+impl RpcSettings {
+  pub fn from_conf(conf: &Configuration) -> Self {
+    RpcSettings {
+      rpc_impl_config: conf.rpc_impl_config(),
+      core_apis: conf.raw_conf.public_rpc_apis.clone(),
+      evm_apis: conf.raw_conf.public_evm_rpc_apis.clone(),
+      dev_mode: conf.is_dev_mode(),
+    }
+  }
+
+  /// The eSpace modules `cfx-rpc-builder` builds for the eth ports, selected
+  /// by `public_evm_rpc_apis`. Light nodes have no eSpace RPC.
+  pub fn eth_modules(
+    &self,
+    components: &NodeComponents,
+    task_manager: &TaskManager,
+    http: bool,
+    ws: bool,
+  ) -> Result<Option<TransportRpcModules>> {
+    let mut transports = TransportRpcModuleConfig::default();
+    if http {
+      transports = transports.with_http(self.evm_apis.clone());
+    }
+    if ws {
+      transports = transports.with_ws(self.evm_apis.clone());
+    }
+    Ok(
+      self
+        .module_builder(components, task_manager)?
+        .map(|builder| builder.build(transports)),
+    )
+  }
+
+  /// The core space modules `cfx-rpc-builder` builds for the core ports,
+  /// selected by `public_rpc_apis`. Light nodes have none.
+  pub fn core_modules(
+    &self,
+    components: &NodeComponents,
+    task_manager: &TaskManager,
+  ) -> Result<Option<RpcModule<()>>> {
+    Ok(
+      self
+        .module_builder(components, task_manager)?
+        .map(|builder| builder.build_core(self.core_apis.clone())),
+    )
+  }
+
+  fn module_builder(
+    &self,
+    components: &NodeComponents,
+    task_manager: &TaskManager,
+  ) -> Result<Option<RpcModuleBuilder>> {
+    let (Ok(sync), Ok(txpool)) = (components.sync(), components.txpool()) else {
+      return Ok(None);
+    };
+    Ok(Some(RpcModuleBuilder::new(
+      self.rpc_impl_config.clone(),
+      components.consensus()?,
+      sync,
+      txpool,
+      task_manager.executor(),
+    )))
+  }
+}
+
+/// JSON-RPC dispatch straight into the node, no server or port involved.
+///
+/// Calls go to the modules `cfx-rpc-builder` builds for the ports, core space
+/// ones selected by `public_rpc_apis` and eSpace ones by
+/// `public_evm_rpc_apis`. Dev mode nodes also get the `xcfx_*` methods that
+/// write account state.
+///
+/// The modules hold the client components, they have to be closed before the
+/// client can shut down.
+pub struct InProcessRpc {
+  module: RwLock<Option<RpcModule<()>>>,
+  next_id: AtomicU64,
+  listeners: Arc<ChainListeners>,
+  // Tasks spawned by the modules stop with the manager.
+  task_manager: Mutex<Option<TaskManager>>,
+}
+
+impl InProcessRpc {
+  pub fn build(
+    settings: &RpcSettings,
+    context: &DevContext,
+    listeners: Arc<ChainListeners>,
+  ) -> Result<Self> {
+    let components = &context.components;
+    let task_manager = TaskManager::new(Handle::current());
+    let merge = |e| NodeError::Initialization(format!("Failed to merge RPC modules: {}", e));
+
+    let mut module = RpcModule::new(());
+    if let Some(core_module) = settings.core_modules(components, &task_manager)? {
+      module.merge(core_module).map_err(merge)?;
+    }
+    let eth_module = settings
+      .eth_modules(components, &task_manager, true, false)?
+      .and_then(|modules| modules.http);
+    if let Some(eth_module) = eth_module {
+      module.merge(eth_module).map_err(merge)?;
+    }
+    if settings.dev_mode {
+      module
+        .merge(cheat_module(context.clone())?)
+        .map_err(merge)?;
+    }
+
+    Ok(InProcessRpc {
+      module: RwLock::new(Some(module)),
+      next_id: AtomicU64::new(1),
+      listeners,
+      task_manager: Mutex::new(Some(task_manager)),
+    })
+  }
+
+  /// Drop the modules, calls still running keep them until they are done.
+  pub fn close(&self) {
+    self.module.write().take();
+    self.task_manager.lock().take();
+  }
+
+  pub async fn call(&self, call: RpcCall) -> Result<RpcResponse> {
+    let module = self
+      .module
+      .read()
+      .clone()
+      .ok_or_else(|| NodeError::Runtime("Node is not running".to_string()))?;
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let request = json!({
+      "jsonrpc": "2.0",
+      "id": id,
+      "method": call.method,
+      "params": call.params.clone().unwrap_or_else(|| json!([])),
+    });
+
+    let (response, _) = module
+      .raw_json_request(&request.to_string(), 1)
+      .await
+      .map_err(|e| NodeError::Runtime(format!("Invalid JSON-RPC request: {}", e)))?;
+    drop(module);
+    let mut response: Value = serde_json::from_str(&response)
+      .map_err(|e| NodeError::Runtime(format!("Invalid JSON-RPC response: {}", e)))?;
+
+    let error = response
+      .get_mut("error")
+      .map(Value::take)
+      .map(|error| RpcError {
+        code: error["code"].as_i64().unwrap_or(INTERNAL_ERROR_CODE as i64) as i32,
+        message: error["message"].as_str().unwrap_or_default().to_string(),
+        data: error.get("data").cloned(),
+      });
+    if let Some(error) = &error {
+      self.report_rejection(&call, error);
+    }
+    Ok(RpcResponse {
+      result: response.get_mut("result").map(Value::take),
+      error,
+    })
+  }
+
+  /// Raw transactions of either space the pool turned down are reported to
+  /// the transaction listeners, the RPC error only reaches the caller.
+  fn report_rejection(&self, call: &RpcCall, error: &RpcError) {
+    if !sends_raw_transaction(&call.method) {
+      return;
+    }
+    let raw = call
+      .params
+      .as_ref()
+      .and_then(|params| params.get(0))
+      .and_then(Value::as_str);
+    // Transactions that don't decode never had a hash to follow.
+    let Some(tx) = raw.and_then(|raw| decode_raw_transaction(raw).ok()) else {
+      return;
+    };
+    self.listeners.transaction.rejected(
+      tx.hash(),
+      Some(tx.sender()),
+      tx.space().into(),
+      &error.message,
+    );
+  }
+}
+
+/// Whether `method` adds a raw transaction of either space to the pool.
+pub fn sends_raw_transaction(method: &str) -> bool {
+  matches!(method, "cfx_sendRawTransaction" | "eth_sendRawTransaction")
+}
+
+/// The `xcfx_*` methods, taking `[address, value, space?]`. The space
+/// defaults to the core space.
+pub fn cheat_module(context: DevContext) -> Result<RpcModule<DevContext>> {
+  let mut module = RpcModule::new(context);
+  let register = |e| NodeError::Initialization(format!("Failed to register RPC method: {}", e));
+
+  module
+    .register_blocking_method("xcfx_setBalance", |params, context, _| {
+      let (address, balance) = cheat_params::<U256>(&params)?;
+      modify_account(&block_producer(&context)?, &address, |account| {
+        account.balance = balance
+      })
+      .map_err(internal_error)
+    })
+    .map_err(register)?;
+
+  module
+    .register_blocking_method("xcfx_setNonce", |params, context, _| {
+      let (address, nonce) = cheat_params::<U256>(&params)?;
+      modify_account(&block_producer(&context)?, &address, |account| {
+        account.nonce = nonce
+      })
+      .map_err(internal_error)
+    })
+    .map_err(register)?;
+
+  module
+    .register_blocking_method("xcfx_setCode", |params, context, _| {
+      let (address, code) = cheat_params::<String>(&params)?;
+      let code = parse_bytes(&code).map_err(invalid_params)?;
+      set_code(&block_producer(&context)?, &address, code).map_err(internal_error)
+    })
+    .map_err(register)?;
+
+  module
+    .register_blocking_method("xcfx_setStorageAt", |params, context, _| {
+      let mut params = params.sequence();
+      let address: String = params.next()?;
+      let slot: String = params.next()?;
+      let value: String = params.next()?;
+      let address =
+        parse_address(&address, parse_space(params.optional_next()?)?).map_err(invalid_params)?;
+      let slot = parse_word(&slot).map_err(invalid_params)?;
+      let value = parse_word(&value).map_err(invalid_params)?;
+
+      set_storage(
+        &block_producer(&context)?,
+        &address,
+        slot,
+        U256::from_big_endian(value.as_bytes()),
+      )
+      .map_err(internal_error)
+    })
+    .map_err(register)?;
+
+  Ok(module)
+}
+
+pub fn block_producer(context: &DevContext) -> RpcResult<BlockProducer> {
+  BlockProducer::new(&context.components, context.clock.clone()).map_err(internal_error)
+}
+
+fn cheat_params<T: DeserializeOwned>(params: &Params) -> RpcResult<(AddressWithSpace, T)> {
+  let mut params = params.sequence();
+  let address: String = params.next()?;
+  let value: T = params.next()?;
+  let space = parse_space(params.optional_next()?)?;
+  let address = parse_address(&address, space).map_err(invalid_params)?;
+  Ok((address, value))
+}
+
+fn parse_space(space: Option<String>) -> RpcResult<Space> {
+  match space.as_deref().map(str::to_lowercase).as_deref() {
+    None | Some("native") => Ok(Space::Native),
+    Some("evm") => Ok(Space::Evm),
+    Some(other) => Err(invalid_params(format!("invalid space {}", other))),
+  }
+}
+
+pub fn invalid_params(message: impl ToString) -> ErrorObjectOwned {
+  ErrorObjectOwned::owned(INVALID_PARAMS_CODE, message.to_string(), None::<()>)
+}
+
+pub fn internal_error(e: impl ToString) -> ErrorObjectOwned {
+  ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, e.to_string(), None::<()>)
+}
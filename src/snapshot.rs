@@ -0,0 +1,118 @@
+use crate::{
+  clock::{Clock, ClockState},
+  components::NodeComponents,
+  error::Result,
+  mining::{run_blocking, BlockProducer},
+  txpool::restore_pool,
+  ConfluxNode,
+};
+use cfx_types::H256;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use primitives::SignedTransaction;
+use std::sync::{
+  atomic::{AtomicU32, Ordering},
+  Arc,
+};
+
+/// The chain as it was when `snapshot` was called.
+struct Snapshot {
+  id: u32,
+  pivot_hash: H256,
+  /// Ready and not yet ready transactions of the pool.
+  pool: Vec<Arc<SignedTransaction>>,
+  clock: ClockState,
+}
+
+/// Snapshots of one node run, oldest first.
+#[derive(Default)]
+pub struct Snapshots {
+  taken: Mutex<Vec<Snapshot>>,
+  next_id: AtomicU32,
+}
+
+impl Snapshots {
+  /// Record the chain, the transaction pool and the node time.
+  pub fn take_snapshot(&self, components: &NodeComponents, clock: &Clock) -> Result<u32> {
+    // Reverting needs the block generator, fail now rather than later.
+    components.blockgen()?;
+    let consensus = components.consensus()?;
+    let (ready, deferred) = components.txpool()?.content(None);
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    self.taken.lock().push(Snapshot {
+      id,
+      pivot_hash: consensus.best_block_hash(),
+      pool: ready.into_iter().chain(deferred).collect(),
+      clock: clock.save(),
+    });
+    Ok(id)
+  }
+
+  /// Go back to snapshot `id`, blocks until the new branch is the pivot chain.
+  /// Returns `false` if there is no such snapshot.
+  pub fn revert(&self, components: &NodeComponents, clock: Arc<Clock>, id: u32) -> Result<bool> {
+    let Some(snapshot) = self.take(id) else {
+      return Ok(false);
+    };
+
+    clock.restore(snapshot.clock);
+    let producer = BlockProducer::new(components, clock)?;
+    producer.fork_to_pivot(snapshot.pivot_hash)?;
+    producer.abandon_other_branches();
+
+    // Transactions of the abandoned blocks are not valid on the new branch.
+    restore_pool(
+      &components.consensus()?,
+      &components.txpool()?,
+      snapshot.pool,
+    );
+    Ok(true)
+  }
+
+  /// Take out snapshot `id` and drop every later one.
+  fn take(&self, id: u32) -> Option<Snapshot> {
+    let mut taken = self.taken.lock();
+    let position = taken.iter().position(|snapshot| snapshot.id == id)?;
+    taken.drain(position..).next()
+  }
+}
+
+/// Cheap isolation between tests that share one running dev node.
+///
+/// The tree-graph can't forget blocks, so `revert` builds a heavier branch of
+/// empty blocks on the pivot block of the snapshot instead. Everything mined
+/// after the snapshot leaves the pivot chain, and the blocks the node mines
+/// later don't reference it, so its state stays gone. Blocks generated by the
+/// test methods of the client's own JSON-RPC ports, e.g.
+/// `test_generateEmptyBlocks`, still do and bring it back. Epoch numbers keep
+/// growing, and the miner keeps the rewards of the new blocks.
+#[napi]
+impl ConfluxNode {
+  /// Record the chain, the transaction pool and the node time. Resolves with
+  /// an id for `revert`.
+  #[napi]
+  pub async fn snapshot(&self) -> Result<u32> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    self.snapshots().await?.take_snapshot(&components, &clock)
+  }
+
+  /// Make the state, pool and node time of snapshot `id` current again. The
+  /// snapshot and all later ones are used up.
+  ///
+  /// Nothing is rolled back, a heavier branch of empty blocks is mined on the
+  /// snapshot instead. Epoch numbers keep growing and the miner keeps the
+  /// rewards of the new blocks.
+  ///
+  /// Resolves with `false` if there is no such snapshot. Automatic mining
+  /// should be paused, blocks mined on the old branch while the revert runs
+  /// delay it.
+  #[napi]
+  pub async fn revert(&self, id: u32) -> Result<bool> {
+    let components = self.dev_components().await?;
+    let clock = self.clock().await?;
+    let snapshots = self.snapshots().await?;
+    run_blocking(move || snapshots.revert(&components, clock, id)).await
+  }
+}
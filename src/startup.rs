@@ -0,0 +1,62 @@
+use crate::error::{NodeError, Result};
+use napi::tokio::{
+  sync::watch,
+  time::{self, Instant},
+};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+
+/// Where `cancel_start` finds the pending start, empty while none is pending.
+pub type StartCanceller = Arc<Mutex<Option<watch::Sender<bool>>>>;
+
+/// Resolves when a pending start has to be given up, either because the
+/// startup deadline passed or because `cancel_start` was called.
+pub struct StartAbort {
+  deadline: Instant,
+  timeout: Duration,
+  cancelled: watch::Receiver<bool>,
+  canceller: StartCanceller,
+}
+
+impl StartAbort {
+  /// Register a new start with `canceller`, it is unregistered again once the
+  /// abort is dropped. Only one start can be pending at a time.
+  pub fn arm(canceller: &StartCanceller, timeout: Duration) -> Result<Self> {
+    let mut slot = canceller.lock();
+    if slot.is_some() {
+      return Err(NodeError::Runtime("Node is already starting".to_string()));
+    }
+    let (cancel_sender, cancelled) = watch::channel(false);
+    *slot = Some(cancel_sender);
+
+    Ok(StartAbort {
+      deadline: Instant::now() + timeout,
+      timeout,
+      cancelled,
+      canceller: canceller.clone(),
+    })
+  }
+
+  pub async fn wait(&mut self) -> NodeError {
+    let deadline = self.deadline;
+    let timeout = self.timeout;
+    let cancelled = &mut self.cancelled;
+
+    napi::tokio::select! {
+      _ = time::sleep_until(deadline) => NodeError::Timeout(format!(
+        "Node did not start within {}ms",
+        timeout.as_millis()
+      )),
+      // A dropped sender means nobody can cancel anymore, so keep waiting for the deadline.
+      Ok(_) = cancelled.wait_for(|cancelled| *cancelled) => {
+        NodeError::Cancelled("Node start was cancelled".to_string())
+      }
+    }
+  }
+}
+
+impl Drop for StartAbort {
+  fn drop(&mut self) {
+    self.canceller.lock().take();
+  }
+}
@@ -0,0 +1,105 @@
+use crate::callback::{CallbackSlot, JsCallback};
+use napi_derive::napi;
+use parking_lot::Mutex;
+
+// Keep the history bounded for nodes that are restarted many times.
+const MAX_TRANSITIONS: usize = 64;
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeState {
+  /// The node has never been started.
+  Idle,
+  Starting,
+  Running,
+  Stopping,
+  Stopped,
+  /// The node failed to boot or died while running, see `last_error`.
+  Crashed,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct StateTransition {
+  pub state: NodeState,
+  /// Unix timestamp in milliseconds.
+  pub timestamp: i64,
+  /// The error that caused this transition, if any.
+  pub error: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+  pub state: NodeState,
+  /// Unix timestamp in milliseconds of the last transition.
+  pub since: i64,
+  pub last_error: Option<String>,
+  /// The most recent transitions, oldest first.
+  pub transitions: Vec<StateTransition>,
+}
+
+pub struct StatusTracker {
+  status: Mutex<NodeStatus>,
+  listener: CallbackSlot<StateTransition>,
+}
+
+impl Default for StatusTracker {
+  fn default() -> Self {
+    let now = chrono::Utc::now().timestamp_millis();
+    StatusTracker {
+      status: Mutex::new(NodeStatus {
+        state: NodeState::Idle,
+        since: now,
+        last_error: None,
+        transitions: vec![StateTransition {
+          state: NodeState::Idle,
+          timestamp: now,
+          error: None,
+        }],
+      }),
+      listener: CallbackSlot::default(),
+    }
+  }
+}
+
+impl StatusTracker {
+  pub fn snapshot(&self) -> NodeStatus {
+    self.status.lock().clone()
+  }
+
+  pub fn set_listener(&self, callback: JsCallback<StateTransition>) {
+    self.listener.set(callback);
+  }
+
+  pub fn transition(&self, state: NodeState) {
+    self.record(state, None);
+  }
+
+  pub fn fail(&self, error: impl ToString) {
+    self.record(NodeState::Crashed, Some(error.to_string()));
+  }
+
+  fn record(&self, state: NodeState, error: Option<String>) {
+    let transition = StateTransition {
+      state,
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      error,
+    };
+
+    {
+      let mut status = self.status.lock();
+      status.state = state;
+      status.since = transition.timestamp;
+      if transition.error.is_some() {
+        status.last_error = transition.error.clone();
+      }
+      if status.transitions.len() == MAX_TRANSITIONS {
+        status.transitions.remove(0);
+      }
+      status.transitions.push(transition.clone());
+    }
+
+    self.listener.notify(transition);
+  }
+}
@@ -0,0 +1,363 @@
+use crate::{
+  callback::{CallbackSlot, JsCallback},
+  error::Result,
+  events::ChainChange,
+  impersonation::sender,
+  query::{hex, parse_address, parse_hash, receipt_info, to_bigint, ReceiptInfo, Space},
+  ConfluxNode,
+};
+use cfx_types::{Address, AddressWithSpace, H256, U256};
+use cfxcore::{ConsensusGraph, TransactionPool};
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use primitives::SignedTransaction;
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStage {
+  /// The transaction entered the pool.
+  Accepted,
+  /// The pool refused the transaction, see `reason`.
+  Rejected,
+  /// The transaction was packed into `block_hash`.
+  Packed,
+  /// The epoch executing the transaction was executed.
+  Executed,
+  /// The receipt is available.
+  Receipt,
+  /// The transaction left the pool without being packed.
+  Dropped,
+  /// A transaction with the same sender and nonce took its place, see
+  /// `replaced_by`.
+  Replaced,
+}
+
+#[napi(object)]
+pub struct TransactionEvent {
+  pub hash: String,
+  pub stage: TransactionStage,
+  pub space: Space,
+  /// `null` if the sender of a rejected transaction could not be recovered.
+  pub from: Option<String>,
+  pub nonce: Option<BigInt>,
+  pub reason: Option<String>,
+  pub block_hash: Option<String>,
+  pub epoch_number: Option<BigInt>,
+  pub receipt: Option<ReceiptInfo>,
+  pub replaced_by: Option<String>,
+}
+
+#[napi(object)]
+pub struct TransactionFilter {
+  pub hashes: Option<Vec<String>>,
+  /// Hex addresses match the sender in both spaces, base32 addresses only in
+  /// the core space.
+  pub senders: Option<Vec<String>>,
+}
+
+/// The JS callback for transaction events and the transactions it wants.
+#[derive(Default)]
+pub struct TransactionListener {
+  callback: CallbackSlot<TransactionEvent>,
+  filter: Mutex<Option<ParsedFilter>>,
+}
+
+struct ParsedFilter {
+  hashes: HashSet<H256>,
+  senders: HashSet<Address>,
+}
+
+impl TransactionListener {
+  pub fn is_set(&self) -> bool {
+    self.callback.is_set()
+  }
+
+  fn matches(&self, hash: &H256, sender: Option<&AddressWithSpace>) -> bool {
+    match self.filter.lock().as_ref() {
+      None => true,
+      Some(filter) => {
+        filter.hashes.contains(hash)
+          || sender.is_some_and(|sender| filter.senders.contains(&sender.address))
+      }
+    }
+  }
+
+  fn notify(&self, event: TransactionEvent, hash: &H256, sender: Option<&AddressWithSpace>) {
+    if self.matches(hash, sender) {
+      self.callback.notify(event);
+    }
+  }
+
+  /// Report a transaction the pool refused. Only the node's own entry points
+  /// and `request` see the refusals, transactions sent to the RPC ports are
+  /// not reported.
+  pub fn rejected(&self, hash: H256, sender: Option<AddressWithSpace>, space: Space, reason: &str) {
+    let event = TransactionEvent {
+      from: sender.map(|sender| hex(&sender.address)),
+      reason: Some(reason.to_string()),
+      ..empty_event(hash, TransactionStage::Rejected, space)
+    };
+    self.notify(event, &hash, sender.as_ref());
+  }
+}
+
+/// Follows transactions from the pool to their receipts.
+pub struct TransactionTracker {
+  tracked: HashMap<H256, Tracked>,
+  /// Executed transactions whose receipt was not indexed yet.
+  awaiting_receipt: HashSet<H256>,
+}
+
+struct Tracked {
+  transaction: Arc<SignedTransaction>,
+  packed: bool,
+}
+
+impl TransactionTracker {
+  /// Transactions already in the pool are not reported as accepted.
+  pub fn new(txpool: &TransactionPool) -> Self {
+    let (ready, deferred) = txpool.content(None);
+    TransactionTracker {
+      tracked: ready
+        .into_iter()
+        .chain(deferred)
+        .map(|tx| (tx.hash(), Tracked::new(tx)))
+        .collect(),
+      awaiting_receipt: HashSet::new(),
+    }
+  }
+
+  /// Report what happened to transactions since the last call, together with
+  /// the chain `changes` of the same period.
+  pub fn update(
+    &mut self,
+    consensus: &ConsensusGraph,
+    txpool: &TransactionPool,
+    changes: &[ChainChange],
+    listener: &TransactionListener,
+  ) {
+    let (ready, deferred) = txpool.content(None);
+    let pool: HashMap<H256, Arc<SignedTransaction>> = ready
+      .into_iter()
+      .chain(deferred)
+      .map(|tx| (tx.hash(), tx))
+      .collect();
+
+    for tx in pool.values() {
+      self.accept(tx, listener);
+    }
+
+    let data_man = consensus.data_manager();
+    for change in changes {
+      match change {
+        // Their transactions are reported again as they are packed and
+        // executed on the new pivot chain.
+        ChainChange::Reverted(_) => {}
+        ChainChange::Mined(epoch, block_hashes) => {
+          for block_hash in block_hashes {
+            let Some(block) = data_man.block_by_hash(block_hash, false) else {
+              continue;
+            };
+            for tx in &block.transactions {
+              self.accept(tx, listener);
+              if let Some(tracked) = self.tracked.get_mut(&tx.hash()) {
+                tracked.packed = true;
+              }
+              listener.notify(
+                TransactionEvent {
+                  block_hash: Some(hex(block_hash)),
+                  epoch_number: Some(BigInt::from(*epoch)),
+                  ..transaction_event(tx, TransactionStage::Packed)
+                },
+                &tx.hash(),
+                Some(&sender(tx)),
+              );
+            }
+          }
+        }
+        ChainChange::Executed(epoch, block_hashes) => {
+          for block_hash in block_hashes {
+            let Some(block) = data_man.block_by_hash(block_hash, false) else {
+              continue;
+            };
+            for tx in &block.transactions {
+              // Transactions packed more than once are executed in the first
+              // block only.
+              let executed_in = data_man
+                .transaction_index_by_hash(&tx.hash(), false)
+                .map(|index| index.block_hash);
+              if executed_in.is_some_and(|executed_in| executed_in != *block_hash) {
+                continue;
+              }
+              listener.notify(
+                TransactionEvent {
+                  block_hash: Some(hex(block_hash)),
+                  epoch_number: Some(BigInt::from(*epoch)),
+                  ..transaction_event(tx, TransactionStage::Executed)
+                },
+                &tx.hash(),
+                Some(&sender(tx)),
+              );
+              self.awaiting_receipt.insert(tx.hash());
+              if let Some(tracked) = self.tracked.get_mut(&tx.hash()) {
+                tracked.packed = true;
+              } else {
+                self.tracked.insert(tx.hash(), Tracked::packed(tx.clone()));
+              }
+            }
+          }
+        }
+      }
+    }
+
+    self.awaiting_receipt.retain(|hash| {
+      let Some(receipt) = receipt_info(consensus, hash) else {
+        return true;
+      };
+      if let Some(tracked) = self.tracked.remove(hash) {
+        let tx = &tracked.transaction;
+        listener.notify(
+          TransactionEvent {
+            block_hash: Some(receipt.block_hash.clone()),
+            epoch_number: Some(receipt.epoch_number.clone()),
+            receipt: Some(receipt),
+            ..transaction_event(tx, TransactionStage::Receipt)
+          },
+          hash,
+          Some(&sender(tx)),
+        );
+      }
+      false
+    });
+
+    // Whatever left the pool unpacked was dropped, or replaced by a
+    // transaction of the same sender and nonce.
+    let packed = self.tracked.values().filter(|tracked| tracked.packed);
+    let by_nonce: HashMap<(AddressWithSpace, U256), H256> = pool
+      .values()
+      .chain(packed.map(|tracked| &tracked.transaction))
+      .map(|tx| ((sender(tx), *tx.nonce()), tx.hash()))
+      .collect();
+    let gone: Vec<H256> = self
+      .tracked
+      .iter()
+      .filter(|(hash, tracked)| !tracked.packed && !pool.contains_key(*hash))
+      .map(|(hash, _)| *hash)
+      .collect();
+    for hash in gone {
+      let Some(tracked) = self.tracked.remove(&hash) else {
+        continue;
+      };
+      let tx = &tracked.transaction;
+      let replaced_by = by_nonce.get(&(sender(tx), *tx.nonce()));
+      let event = match replaced_by {
+        Some(replaced_by) => TransactionEvent {
+          replaced_by: Some(hex(replaced_by)),
+          ..transaction_event(tx, TransactionStage::Replaced)
+        },
+        None => transaction_event(tx, TransactionStage::Dropped),
+      };
+      listener.notify(event, &hash, Some(&sender(tx)));
+    }
+  }
+
+  /// Report `tx` as accepted the first time it is seen.
+  fn accept(&mut self, tx: &Arc<SignedTransaction>, listener: &TransactionListener) {
+    if self.tracked.contains_key(&tx.hash()) {
+      return;
+    }
+    self.tracked.insert(tx.hash(), Tracked::new(tx.clone()));
+    listener.notify(
+      transaction_event(tx, TransactionStage::Accepted),
+      &tx.hash(),
+      Some(&sender(tx)),
+    );
+  }
+}
+
+impl Tracked {
+  fn new(transaction: Arc<SignedTransaction>) -> Self {
+    Tracked {
+      transaction,
+      packed: false,
+    }
+  }
+
+  fn packed(transaction: Arc<SignedTransaction>) -> Self {
+    Tracked {
+      transaction,
+      packed: true,
+    }
+  }
+}
+
+fn empty_event(hash: H256, stage: TransactionStage, space: Space) -> TransactionEvent {
+  TransactionEvent {
+    hash: hex(&hash),
+    stage,
+    space,
+    from: None,
+    nonce: None,
+    reason: None,
+    block_hash: None,
+    epoch_number: None,
+    receipt: None,
+    replaced_by: None,
+  }
+}
+
+fn transaction_event(tx: &SignedTransaction, stage: TransactionStage) -> TransactionEvent {
+  TransactionEvent {
+    from: Some(hex(&sender(tx).address)),
+    nonce: Some(to_bigint(tx.nonce())),
+    ..empty_event(tx.hash(), stage, tx.space().into())
+  }
+}
+
+#[napi]
+impl ConfluxNode {
+  /// Register a callback that follows transactions of both spaces through the
+  /// pool, into blocks and to their receipts. With a `filter`, only
+  /// transactions with one of its hashes or senders are reported.
+  ///
+  /// Rejections are reported for the methods of this class and for
+  /// `cfx_sendRawTransaction` and `eth_sendRawTransaction` sent through
+  /// `request`. Transactions the RPC ports refuse are not reported, the ports
+  /// answer them themselves. Registering a new callback replaces the previous
+  /// one.
+  #[napi]
+  pub fn on_transaction(
+    &self,
+    callback: JsCallback<TransactionEvent>,
+    filter: Option<TransactionFilter>,
+  ) -> Result<()> {
+    let filter = filter
+      .map(|filter| -> Result<_> {
+        Ok(ParsedFilter {
+          hashes: filter
+            .hashes
+            .unwrap_or_default()
+            .iter()
+            .map(|hash| parse_hash(hash))
+            .collect::<Result<_>>()?,
+          senders: filter
+            .senders
+            .unwrap_or_default()
+            .iter()
+            .map(|sender| parse_address(sender, Space::Native).map(|a| a.address))
+            .collect::<Result<_>>()?,
+        })
+      })
+      .transpose()?;
+
+    let listener = &self.chain_listeners.transaction;
+    *listener.filter.lock() = filter;
+    listener.callback.set(callback);
+    Ok(())
+  }
+}
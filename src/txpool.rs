@@ -0,0 +1,255 @@
+use crate::{
+  error::{NodeError, Result},
+  query::{account_state, parse_hash, to_bigint, transaction_info, Space, TransactionInfo},
+  ConfluxNode,
+};
+use cfx_parameters::staking::COLLATERAL_DRIPS_PER_BYTE;
+use cfx_types::{AddressWithSpace, H256, U256};
+use cfxcore::{ConsensusGraph, TransactionPool};
+use log::warn;
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use primitives::{EpochNumber, SignedTransaction};
+use std::{collections::HashMap, sync::Arc};
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotReadyReason {
+  /// The nonce is below the sender's nonce, the transaction will be dropped.
+  StaleNonce,
+  /// A transaction with an earlier nonce of the sender is missing.
+  NonceGap,
+  /// The sender can't pay value and gas of this and its earlier transactions.
+  NotEnoughCash,
+  /// The sender can't pay the collateral for the storage limit.
+  NotEnoughCashForStorage,
+  GasPriceTooLow,
+  /// The gas limit is above the maximum of a single transaction.
+  GasLimitTooHigh,
+  /// None of the above, the pool did not re-evaluate the transaction yet.
+  Unknown,
+}
+
+#[napi(object)]
+pub struct QueuedTransaction {
+  pub transaction: TransactionInfo,
+  pub reason: NotReadyReason,
+}
+
+#[napi(object)]
+pub struct PoolContent {
+  /// Transactions that can be packed into the next block.
+  pub pending: Vec<TransactionInfo>,
+  pub queued: Vec<QueuedTransaction>,
+}
+
+#[napi(object)]
+pub struct PoolLimits {
+  /// The maximum number of transactions in the pool.
+  pub capacity: u32,
+  pub min_native_gas_price: BigInt,
+  pub min_evm_gas_price: BigInt,
+  /// The maximum gas limit of a single transaction.
+  pub max_transaction_gas: BigInt,
+  /// Transactions in the pool right now that are not packed yet.
+  pub unpacked: u32,
+}
+
+/// Inspection and control of the transaction pool.
+#[napi]
+impl ConfluxNode {
+  /// Transactions in the pool, of one space or both. Queued transactions come
+  /// with the reason they can't be packed yet.
+  ///
+  /// Reasons are worked out from the latest executed state, gas sponsorship is
+  /// not taken into account.
+  #[napi]
+  pub async fn get_pool_content(&self, space: Option<Space>) -> Result<PoolContent> {
+    let components = self.components().await?;
+    let consensus = components.consensus()?;
+    let txpool = components.txpool()?;
+
+    let in_space = |tx: &Arc<SignedTransaction>| space.is_none_or(|s| Space::from(tx.space()) == s);
+    let (ready, deferred) = txpool.content(None);
+    let ready: Vec<_> = ready.into_iter().filter(in_space).collect();
+    let deferred: Vec<_> = deferred.into_iter().filter(in_space).collect();
+
+    let reasons = not_ready_reasons(&consensus, &txpool, &ready, &deferred)?;
+    Ok(PoolContent {
+      pending: ready.iter().map(|tx| transaction_info(tx, None)).collect(),
+      queued: deferred
+        .iter()
+        .zip(reasons)
+        .map(|(tx, reason)| QueuedTransaction {
+          transaction: transaction_info(tx, None),
+          reason,
+        })
+        .collect(),
+    })
+  }
+
+  /// Remove transaction `hash` from the pool. Later transactions of the same
+  /// sender stay and wait for the nonce to be filled again.
+  ///
+  /// Resolves with `false` if the transaction is not in the pool. Transactions
+  /// a block packed already can't be dropped.
+  #[napi]
+  pub async fn drop_transaction(&self, hash: String) -> Result<bool> {
+    let hash = parse_hash(&hash)?;
+    let components = self.components().await?;
+    let consensus = components.consensus()?;
+    let txpool = components.txpool()?;
+
+    if is_packed(&consensus, &hash) {
+      return Err(NodeError::Runtime(format!(
+        "Transaction {:#x} is packed already",
+        hash
+      )));
+    }
+    Ok(txpool.remove_tx_by_hash(&hash).is_some())
+  }
+
+  /// Remove every transaction from the pool.
+  #[napi]
+  pub async fn clear_pool(&self) -> Result<()> {
+    self.components().await?.txpool()?.clear_tx_pool();
+    Ok(())
+  }
+
+  #[napi]
+  pub async fn get_pool_limits(&self) -> Result<PoolLimits> {
+    let txpool = self.components().await?.txpool()?;
+    let config = &txpool.config;
+    Ok(PoolLimits {
+      capacity: config.capacity.min(u32::MAX as usize) as u32,
+      min_native_gas_price: BigInt::from(config.min_native_tx_price),
+      min_evm_gas_price: BigInt::from(config.min_eth_tx_price),
+      max_transaction_gas: to_bigint(&config.max_tx_gas.read()),
+      unpacked: txpool.total_unpacked().min(u32::MAX as usize) as u32,
+    })
+  }
+}
+
+/// Whether `hash` is in a block of the pivot chain, executed or not.
+fn is_packed(consensus: &ConsensusGraph, hash: &H256) -> bool {
+  let data_man = consensus.data_manager();
+  // Blocks left off the pivot chain keep the index of what they executed.
+  let index = data_man.transaction_index_by_hash(hash, false);
+  if index.is_some_and(|index| {
+    consensus
+      .get_block_epoch_number(&index.block_hash)
+      .is_some()
+  }) {
+    return true;
+  }
+  // The index is written on execution, which runs behind the latest epochs.
+  let first_unexecuted = consensus.best_executed_state_epoch_number() + 1;
+  (first_unexecuted..=consensus.best_epoch_number()).any(|epoch| {
+    let block_hashes = consensus
+      .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+      .unwrap_or_default();
+    block_hashes.iter().any(|block_hash| {
+      data_man
+        .block_by_hash(block_hash, false)
+        .is_some_and(|block| block.transactions.iter().any(|tx| tx.hash() == *hash))
+    })
+  })
+}
+
+/// Put the pool back to `transactions`, taken from it earlier.
+///
+/// Everything in the pool now goes out one by one, transactions that arrive
+/// meanwhile stay. The given ones are inserted again unpacked, unless the
+/// pivot chain packs them already.
+pub fn restore_pool(
+  consensus: &ConsensusGraph,
+  txpool: &TransactionPool,
+  transactions: Vec<Arc<SignedTransaction>>,
+) {
+  let (ready, deferred) = txpool.content(None);
+  for tx in ready.iter().chain(&deferred).chain(&transactions) {
+    txpool.remove_tx_by_hash(&tx.hash());
+  }
+  let transactions = transactions
+    .into_iter()
+    .filter(|tx| !is_packed(consensus, &tx.hash()))
+    .collect();
+
+  // They were verified on the way in, including the unsigned ones of
+  // impersonated senders.
+  let (_, failures) = txpool.insert_new_signed_transactions(transactions);
+  for (hash, reason) in failures {
+    warn!(
+      "Transaction {:#x} could not be restored to the pool: {}",
+      hash, reason
+    );
+  }
+}
+
+/// Why each of `deferred` is not ready, checked the way the pool does in nonce
+/// order per sender.
+fn not_ready_reasons(
+  consensus: &ConsensusGraph,
+  txpool: &TransactionPool,
+  ready: &[Arc<SignedTransaction>],
+  deferred: &[Arc<SignedTransaction>],
+) -> Result<Vec<NotReadyReason>> {
+  let mut by_sender: HashMap<AddressWithSpace, Vec<&Arc<SignedTransaction>>> = HashMap::new();
+  for tx in ready.iter().chain(deferred) {
+    by_sender.entry(tx.sender()).or_default().push(tx);
+  }
+
+  let mut reasons = HashMap::new();
+  for (sender, mut txs) in by_sender {
+    txs.sort_by_key(|tx| *tx.nonce());
+    let account = account_state(consensus, &sender, EpochNumber::LatestState)?;
+    let balance = account.as_ref().map(|a| a.balance).unwrap_or_default();
+    let mut next_nonce = account.map(|a| a.nonce).unwrap_or_default();
+    let mut cost = U256::zero();
+
+    for tx in txs {
+      let reason = if *tx.nonce() < next_nonce {
+        NotReadyReason::StaleNonce
+      } else if *tx.nonce() > next_nonce {
+        NotReadyReason::NonceGap
+      } else {
+        next_nonce += U256::one();
+        cost = cost.saturating_add(
+          tx.value()
+            .saturating_add(tx.gas().saturating_mul(*tx.gas_price())),
+        );
+        let collateral = U256::from(tx.storage_limit().unwrap_or_default())
+          .saturating_mul(U256::from(COLLATERAL_DRIPS_PER_BYTE));
+        let min_gas_price = match Space::from(tx.space()) {
+          Space::Native => txpool.config.min_native_tx_price,
+          Space::Evm => txpool.config.min_eth_tx_price,
+        };
+
+        if balance < cost {
+          NotReadyReason::NotEnoughCash
+        } else if balance < cost.saturating_add(collateral) {
+          NotReadyReason::NotEnoughCashForStorage
+        } else if *tx.gas_price() < U256::from(min_gas_price) {
+          NotReadyReason::GasPriceTooLow
+        } else if *tx.gas() > *txpool.config.max_tx_gas.read() {
+          NotReadyReason::GasLimitTooHigh
+        } else {
+          NotReadyReason::Unknown
+        }
+      };
+      reasons.insert(tx.hash(), reason);
+    }
+  }
+
+  Ok(
+    deferred
+      .iter()
+      .map(|tx| {
+        reasons
+          .get(&tx.hash())
+          .copied()
+          .unwrap_or(NotReadyReason::Unknown)
+      })
+      .collect(),
+  )
+}
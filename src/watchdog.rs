@@ -0,0 +1,70 @@
+use log::warn;
+use napi::tokio::{
+  sync::oneshot,
+  task,
+  time::{self, Instant},
+};
+use parking_lot::Mutex;
+use std::{future::Future, sync::Arc, time::Duration};
+
+/// The last time the host proved it is still alive.
+pub struct Heartbeat {
+  last: Mutex<Instant>,
+}
+
+impl Default for Heartbeat {
+  fn default() -> Self {
+    Heartbeat {
+      last: Mutex::new(Instant::now()),
+    }
+  }
+}
+
+impl Heartbeat {
+  pub fn beat(&self) {
+    *self.last.lock() = Instant::now();
+  }
+
+  fn deadline(&self, timeout: Duration) -> Instant {
+    *self.last.lock() + timeout
+  }
+}
+
+/// Runs `on_expire` once no heartbeat arrived for `timeout`.
+///
+/// The watchdog belongs to one node run and is disarmed when it is dropped.
+pub struct Watchdog {
+  _disarm: oneshot::Sender<()>,
+}
+
+impl Watchdog {
+  pub fn arm<F>(heartbeat: Arc<Heartbeat>, timeout: Duration, on_expire: F) -> Self
+  where
+    F: Future<Output = ()> + Send + 'static,
+  {
+    heartbeat.beat();
+    let (disarm, mut disarmed) = oneshot::channel::<()>();
+
+    task::spawn(async move {
+      loop {
+        let deadline = heartbeat.deadline(timeout);
+        if Instant::now() >= deadline {
+          break;
+        }
+        napi::tokio::select! {
+          // Resolves with an error once the sender is dropped.
+          _ = &mut disarmed => return,
+          _ = time::sleep_until(deadline) => {}
+        }
+      }
+
+      warn!(
+        "No heartbeat from the host for {}ms, shutting the node down",
+        timeout.as_millis()
+      );
+      on_expire.await;
+    });
+
+    Watchdog { _disarm: disarm }
+  }
+}